@@ -0,0 +1,201 @@
+
+pub mod helper;
+
+use crate::{language::Language, readability, segmentation};
+pub use helper::{CloneMetric, WordsMatching};
+
+/// A whitespace-separated piece of the text, as given to the metrics.
+pub struct Token<'a> {
+	pub text: &'a str,
+	/// The words of the token, none for punctuation or numbers alone.
+	pub words: &'a [&'a str],
+	/// Whether the token starts a new sentence.
+	pub sentence_start: bool,
+}
+
+/// How the values of a metric over several texts make their total.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Total {
+	Sum,
+	Min,
+	Max,
+	/// The metric has no meaningful total.
+	None,
+}
+
+impl Total {
+	pub fn combine(&self, a: f64, b: f64) -> f64 {
+		match self {
+			Total::Sum => a + b,
+			Total::Min => a.min(b),
+			Total::Max => a.max(b),
+			Total::None => f64::NAN,
+		}
+	}
+}
+
+/// A figure computed in the single pass over a text. Implement it, with
+/// `Clone`, and add the metric to [`crate::Options::metrics`] to have it
+/// computed for every input; every analysed text starts from a clone of
+/// the registered metric.
+pub trait Metric: CloneMetric + Send + Sync {
+	/// Name of the metric in the reports.
+	fn name(&self) -> &str;
+	/// Called with every token of the text, in order.
+	fn update(&mut self, token: &Token);
+	/// The value once the whole text has been seen.
+	fn finalize(&self) -> f64;
+	/// How to make the total of several texts, a sum by default.
+	fn total(&self) -> Total {
+		Total::Sum
+	}
+}
+
+impl Clone for Box<dyn Metric> {
+	fn clone(&self) -> Box<dyn Metric> {
+		self.clone_metric()
+	}
+}
+
+/// The value of a metric for a text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetricValue {
+	pub name: String,
+	pub value: f64,
+	pub total: Total,
+}
+
+impl MetricValue {
+	pub fn of(metric: &dyn Metric) -> MetricValue {
+		MetricValue { name: metric.name().to_string(), value: metric.finalize(), total: metric.total() }
+	}
+
+	/// Adds the value of the same metric for another text.
+	pub fn merge(&mut self, other: &MetricValue) {
+		self.value = self.total.combine(self.value, other.value);
+	}
+}
+
+/// Number of non-whitespace characters, counted as grapheme clusters.
+#[derive(Clone, Default)]
+pub struct Characters(usize);
+
+impl Metric for Characters {
+	fn name(&self) -> &str {
+		"characters"
+	}
+	fn update(&mut self, token: &Token) {
+		self.0 += segmentation::grapheme_count(token.text);
+	}
+	fn finalize(&self) -> f64 {
+		self.0 as f64
+	}
+}
+
+/// Number of Unicode code points of the non-whitespace characters.
+#[derive(Clone, Default)]
+pub struct CodePoints(usize);
+
+impl Metric for CodePoints {
+	fn name(&self) -> &str {
+		"code points"
+	}
+	fn update(&mut self, token: &Token) {
+		self.0 += token.text.chars().count();
+	}
+	fn finalize(&self) -> f64 {
+		self.0 as f64
+	}
+}
+
+/// Size in UTF-8 of the non-whitespace characters.
+#[derive(Clone, Default)]
+pub struct Bytes(usize);
+
+impl Metric for Bytes {
+	fn name(&self) -> &str {
+		"bytes"
+	}
+	fn update(&mut self, token: &Token) {
+		self.0 += token.text.len();
+	}
+	fn finalize(&self) -> f64 {
+		self.0 as f64
+	}
+}
+
+#[derive(Clone, Default)]
+pub struct Words(usize);
+
+impl Metric for Words {
+	fn name(&self) -> &str {
+		"words"
+	}
+	fn update(&mut self, token: &Token) {
+		self.0 += token.words.len();
+	}
+	fn finalize(&self) -> f64 {
+		self.0 as f64
+	}
+}
+
+/// Number of syllables of the words, and of the words with three or more
+/// of them, by the rules of a language.
+#[derive(Clone, Default)]
+pub struct Syllables {
+	language: Language,
+	syllables: usize,
+	polysyllables: usize,
+}
+
+impl Syllables {
+	pub fn new(language: Language) -> Syllables {
+		Syllables { language, ..Syllables::default() }
+	}
+
+	pub fn polysyllables(&self) -> usize {
+		self.polysyllables
+	}
+}
+
+impl Metric for Syllables {
+	fn name(&self) -> &str {
+		"syllables"
+	}
+	fn update(&mut self, token: &Token) {
+		for word in token.words {
+			let syllables = readability::syllables(word, self.language);
+			self.syllables += syllables;
+			if syllables >= 3 {
+				self.polysyllables += 1;
+			}
+		}
+	}
+	fn finalize(&self) -> f64 {
+		self.syllables as f64
+	}
+}
+
+/// The counters every analysis runs.
+#[derive(Clone, Default)]
+pub struct Counters {
+	pub characters: Characters,
+	pub code_points: CodePoints,
+	pub bytes: Bytes,
+	pub words: Words,
+	pub syllables: Syllables,
+}
+
+impl Counters {
+	pub fn new(language: Language) -> Counters {
+		Counters { syllables: Syllables::new(language), ..Counters::default() }
+	}
+
+	pub fn update(&mut self, token: &Token) {
+		self.characters.update(token);
+		self.code_points.update(token);
+		self.bytes.update(token);
+		self.words.update(token);
+		self.syllables.update(token);
+	}
+}
@@ -0,0 +1,59 @@
+
+use std::{collections::HashSet, fs::read_to_string, io, path::Path, sync::Arc};
+use super::{Metric, Token};
+
+/// Clones a boxed metric; implemented for every `Clone` metric.
+pub trait CloneMetric {
+	fn clone_metric(&self) -> Box<dyn Metric>;
+}
+
+impl<T: Metric + Clone + 'static> CloneMetric for T {
+	fn clone_metric(&self) -> Box<dyn Metric> {
+		Box::new(self.clone())
+	}
+}
+
+/// Counts the words accepted by a predicate, like the terms of a domain.
+#[derive(Clone)]
+pub struct WordsMatching {
+	name: String,
+	accepts: Arc<dyn Fn(&str) -> bool + Send + Sync>,
+	count: usize,
+}
+
+impl WordsMatching {
+	pub fn new<F: Fn(&str) -> bool + Send + Sync + 'static>(name: &str, accepts: F) -> WordsMatching {
+		WordsMatching { name: name.to_string(), accepts: Arc::new(accepts), count: 0 }
+	}
+
+	/// Counts the words of a list, case-insensitively.
+	pub fn from_list<I: IntoIterator<Item = String>>(name: &str, words: I) -> WordsMatching {
+		let words: HashSet<String> = words.into_iter().map(|word| word.to_lowercase()).collect();
+		WordsMatching::new(name, move |word| words.contains(&word.to_lowercase()))
+	}
+
+	/// Counts the words listed in the file at `path`, separated by
+	/// whitespace; '#' starts a comment. The metric is named after the file.
+	pub fn from_file(path: &Path) -> io::Result<WordsMatching> {
+		let content = read_to_string(path)?;
+		let words = content
+			.lines()
+			.flat_map(|line| line.split('#').next().unwrap_or("").split_whitespace())
+			.map(str::to_string)
+			.collect::<Vec<String>>();
+		let name = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+		Ok(WordsMatching::from_list(&name, words))
+	}
+}
+
+impl Metric for WordsMatching {
+	fn name(&self) -> &str {
+		&self.name
+	}
+	fn update(&mut self, token: &Token) {
+		self.count += token.words.iter().filter(|word| (self.accepts)(word)).count();
+	}
+	fn finalize(&self) -> f64 {
+		self.count as f64
+	}
+}
@@ -0,0 +1,690 @@
+
+use std::{env, fs::read_dir, path::{Path, PathBuf}, sync::Arc};
+use text_analyser::{
+	analyser::WordsMatching,
+	config::{self, Config, Setting},
+	corpus::{self, glob_match, Filter},
+	encoding::Encoding,
+	error::Error,
+	extract::Markup,
+	frequency::Order,
+	keywords::Idf,
+	language::Language,
+	report::Format,
+	stopwords::StopWords,
+	style::StyleRules,
+	Options,
+};
+
+pub const USAGE: &str = "\
+usage: text_analyser [OPTIONS] [PATH]...
+
+Analyse one or more text files and print their statistics.
+
+arguments:
+  PATH          a file, a directory (every file inside is analysed,
+                recursively) or a pattern using '*' and '?' in its
+                file name. '-' reads the text from the standard input.
+                Without any PATH, the path is asked interactively.
+
+options:
+      --include GLOB
+                in directories, only analyse the files matching GLOB, can
+                be repeated. A GLOB without '/' is matched against the
+                file name, otherwise against the path relative to the
+                directory, '**' standing for any number of directories
+      --exclude GLOB
+                in directories, leave out the files and directories
+                matching GLOB, can be repeated
+  -j, --jobs N  number of files analysed in parallel (default: one per
+                processor)
+  -e, --encoding ENCODING
+                encoding of the inputs: utf-8, utf-16le, utf-16be, latin-1
+                or windows-1252 (default: detected from the content)
+      --markup MARKUP
+                markup left out of the inputs: plain, front-matter (plain
+                text after a YAML or TOML header), markdown, html or rust
+                (only the comments and string literals of the source).
+                By default it is chosen from the file extension
+  -w, --watch   keep running and analyse the files again each time they
+                are saved, showing what changed since the last run. The
+                modification time and the size of the files are polled,
+                without inotify, and a changed file is read again whole
+      --interval MS
+                how often the watched files are checked, in milliseconds
+                (default 500)
+  -c, --compare compare the vocabulary and the statistics of exactly two
+                inputs
+  -f, --format FORMAT
+                output format: text (default), json or csv. The JSON
+                is always an object with the report of every input in
+                \"files\" and the report of all of them in \"total\"
+  -n, --top N   number of words in the frequency table, 0 for all
+                (default 10)
+  -k, --keywords N
+                report the N words with the highest TF-IDF of each input,
+                0 for all. The document frequencies come from the inputs
+                analysed together, added to the table of --idf
+      --idf PATH
+                reuse the document frequencies saved in PATH
+      --save-idf PATH
+                save the document frequencies of the inputs (and of
+                --idf) to PATH
+      --ascending
+                list the least used words first
+  -i, --ignore-case
+                count words case-insensitively
+  -p, --strip-punctuation
+                leave out the punctuation of words before counting them:
+                \"don't\" and \"e.g.\" count as \"dont\" and \"eg\"
+      --normalize FORM
+                unicode normalisation of words: none (default), nfc or nfkc
+      --reduce MODE
+                group the inflected forms of English and French words in
+                the counts: none (default), stem (\"running\" and \"runs\"
+                count as \"run\") or lemma (\"went\" counts as \"go\")
+      --histogram
+                draw the distribution of word and phrase lengths
+      --lines   report the lines: blank ones, the longest, line breaks,
+                indentation and trailing whitespace
+      --max-width N
+                report the lines longer than N characters
+      --lint    only list the problems of the lines (too long with
+                --max-width, trailing whitespace, mixed indentation, mixed
+                line breaks), the unknown words with --spell and the
+                problems of style with --style as FILE:LINE:COLUMN, and
+                exit with status 1 when there is any
+  -l, --language LANG
+                language of the text, for the syllable rules and the
+                abbreviations: en (default), fr, es, de, or auto to use
+                the language detected from the start of each input
+      --max-grade GRADE
+                exit with status 1 when the Flesch-Kincaid grade of an
+                input is above GRADE
+  -s, --stop-words LANG[,LANG]...
+                leave out the bundled stop words of the languages
+                (en, fr, es, de) from the frequency table; auto stands
+                for the detected language when --language is auto
+      --stop-words-file PATH
+                leave out the words listed in PATH, can be repeated
+      --terms PATH
+                count the words listed in PATH, reported under the name
+                of the file, can be repeated
+      --spell DICT
+                check the spelling against the words of DICT, a Hunspell
+                dictionary (.dic, with the .aff file beside it) or a plain
+                list of words, can be repeated. The unknown words are
+                reported with their position and the closest known words
+      --spell-ignore PATH
+                words listed in PATH are never reported as unknown, can
+                be repeated. A .spelling file in the current directory is
+                always used
+      --style   report the problems of style: passive voice, weasel words,
+                sentences with more than 2 adverbs, doubled words (\"the
+                the\") and sentences over 35 words. The rules made of
+                English words only apply to English texts
+      --style-rules PATH
+                check the style with the rules of PATH, one 'RULE = VALUE'
+                per line: passive-voice, weasel-words or doubled-words set
+                to true or false, adverbs and long-sentence to true, false
+                or their limit
+      --style-rule RULE=VALUE
+                check the style with RULE set to VALUE, as in a line of
+                --style-rules, can be repeated
+      --ngrams N[,N]...
+                report the most frequent sequences of N words
+      --ngram-min N
+                least number of occurrences of a reported n-gram
+                (default 2)
+      --config PATH
+                read the default options from PATH instead of the
+                configuration files found
+      --no-config
+                ignore the configuration files
+  -h, --help    print this help and exit
+
+Options without a value can be given '=true' or '=false', as in
+'--lines=false' to turn off an option set in a configuration file.
+
+configuration:
+  The options are first read from $XDG_CONFIG_HOME/text_analyser/config.toml
+  (~/.config when XDG_CONFIG_HOME is not set), then from the nearest
+  text_analyser.toml or .text_analyser.toml in the working directory or
+  its parents; the command line comes last and wins. Every key is the name
+  of a long option, with the value given on the command line: a string, a
+  number or true and false for an option without a value. An array stands
+  for the option repeated, or for a list with --stop-words and --ngrams;
+  such an option given on the command line replaces the values of the
+  files instead of adding to them. Paths are relative to the file. The
+  rules of --style-rules go in a [style] table; in a file, they only tune
+  the check turned on with style = true or --style. For example:
+    top = 20
+    ignore-case = true
+    stop-words = [\"en\", \"fr\"]
+    spell = \"dict/en_US.dic\"
+    [style]
+    long-sentence = 30
+
+exit status:
+  0  every input was analysed
+  1  an input is above --max-grade, or --lint found a problem
+  2  invalid command line
+  3  a path does not exist
+  4  a path is neither a file nor a directory
+  5  permission denied
+  6  an input is not valid in its encoding
+  7  nothing to analyse (no path, or an empty directory or pattern)
+  8  any other read error
+When several inputs fail, the status is the one of the first error; the
+other inputs are still analysed.";
+
+pub enum Input {
+	Stdin,
+	File(PathBuf),
+}
+
+impl Input {
+	pub fn name(&self) -> String {
+		match self {
+			Input::Stdin => "<stdin>".to_string(),
+			Input::File(path) => path.display().to_string(),
+		}
+	}
+}
+
+pub struct Args {
+	pub help: bool,
+	pub format: Format,
+	pub top: Option<usize>,
+	pub order: Order,
+	pub options: Options,
+	pub max_grade: Option<f64>,
+	pub histogram: bool,
+	pub lines: bool,
+	pub lint: bool,
+	pub filter: Filter,
+	pub jobs: usize,
+	pub compare: bool,
+	pub watch: bool,
+	/// Milliseconds between two checks of the watched files.
+	pub interval: u64,
+	pub encoding: Option<Encoding>,
+	/// Number of keywords per input, 0 for all.
+	pub keywords: Option<usize>,
+	/// Document frequencies loaded with `--idf`.
+	pub idf: Option<Idf>,
+	pub save_idf: Option<PathBuf>,
+	/// Markup of every input, from the file extension when `None`.
+	pub markup: Option<Markup>,
+	/// Ignore lists of the spelling check, added once the dictionary is
+	/// complete.
+	ignore_lists: Vec<PathBuf>,
+	/// Whether the style is checked, with `style_rules`.
+	style: bool,
+	/// The style rules tuned so far, kept apart from `style` for the
+	/// configuration files to tune them without turning the check on.
+	style_rules: StyleRules,
+	/// Lists filled by a configuration file, which the command line
+	/// replaces instead of adding to them.
+	configured: Vec<&'static str>,
+	pub paths: Vec<String>,
+}
+
+/// Value of a switch: on without a value, or as given by `--option=true`
+/// or `--option=false`.
+fn flag(arg: &str, inline: Option<&str>) -> Result<bool, String> {
+	match inline {
+		None | Some("true") => Ok(true),
+		Some("false") => Ok(false),
+		Some(value) => Err(format!("invalid value '{}' for '{}' (expected true or false)", value, arg)),
+	}
+}
+
+/// Splits `--option=value` and fetches the value of `--option value`.
+fn option_value<I: Iterator<Item = String>>(arg: &str, inline: Option<&str>, args: &mut I) -> Result<String, String> {
+	match inline {
+		Some(value) => Ok(value.to_string()),
+		None => args.next().ok_or(format!("option '{}' needs a value", arg)),
+	}
+}
+
+fn number<I: Iterator<Item = String>>(arg: &str, inline: Option<&str>, args: &mut I) -> Result<usize, String> {
+	let value = option_value(arg, inline, args)?;
+	value.parse().map_err(|_| format!("invalid number '{}' for '{}'", value, arg))
+}
+
+impl Args {
+	/// The options when nothing is given.
+	pub fn new() -> Args {
+		Args {
+			help: false,
+			format: Format::Text,
+			top: Some(10),
+			order: Order::Descending,
+			options: Options::default(),
+			max_grade: None,
+			histogram: false,
+			lines: false,
+			lint: false,
+			filter: Filter::default(),
+			jobs: corpus::default_jobs(),
+			compare: false,
+			watch: false,
+			interval: 500,
+			encoding: None,
+			keywords: None,
+			idf: None,
+			save_idf: None,
+			markup: None,
+			ignore_lists: Vec::new(),
+			style: false,
+			style_rules: StyleRules::default(),
+			configured: Vec::new(),
+			paths: Vec::new(),
+		}
+	}
+
+	/// Sets the options of the command line `args`, over the ones already
+	/// set.
+	pub fn read<I: Iterator<Item = String>>(&mut self, mut args: I) -> Result<(), String> {
+		let mut only_paths = false;
+
+		while let Some(arg) = args.next() {
+			if only_paths || arg == "-" || !arg.starts_with('-') {
+				self.paths.push(arg);
+				continue;
+			}
+			let (name, inline) = match arg.split_once('=') {
+				Some((name, value)) if name.starts_with("--") => (name, Some(value)),
+				_ => (arg.as_str(), None),
+			};
+			match name {
+				"--" => only_paths = true,
+				// On the command line, tuning the rules turns the check on.
+				"--style-rules" | "--style-rule" => {
+					self.set(name, inline, &mut args)?;
+					self.style = true;
+				}
+				_ => {
+					self.clear_configured(name);
+					self.set(name, inline, &mut args)?;
+				}
+			}
+		}
+		self.options.style = self.style.then(|| self.style_rules.clone());
+		self.options.line_stats = self.lines || self.lint || self.options.max_line_width.is_some();
+		self.load_ignore_lists()
+	}
+
+	/// Empties the list the option `name` adds to when it was filled by a
+	/// configuration file, for the command line to win.
+	fn clear_configured(&mut self, name: &str) {
+		let Some(list) = list_of(name) else {
+			return;
+		};
+		if !self.configured.contains(&list) {
+			return;
+		}
+		self.configured.retain(|configured| *configured != list);
+		match list {
+			"include" => self.filter.include.clear(),
+			"exclude" => self.filter.exclude.clear(),
+			"stop-words" => {
+				self.options.words.stop_words = StopWords::new();
+				self.options.auto_stop_words = false;
+			}
+			"ngrams" => self.options.ngrams.clear(),
+			"terms" => self.options.metrics.clear(),
+			"spell" => self.options.dictionary = None,
+			"spell-ignore" => self.ignore_lists.clear(),
+			_ => (),
+		}
+	}
+
+	/// Sets the option `name`, taking its value from `inline` or else from
+	/// the next of `args`.
+	fn set<I: Iterator<Item = String>>(&mut self, name: &str, inline: Option<&str>, args: &mut I) -> Result<(), String> {
+		match name {
+			"-h" | "--help" => self.help = flag(name, inline)?,
+			"-f" | "--format" => self.format = option_value(name, inline, args)?.parse()?,
+			"-n" | "--top" => {
+				self.top = match number(name, inline, args)? {
+					0 => None,
+					n => Some(n),
+				};
+			}
+			"--include" => self.filter.include.push(option_value(name, inline, args)?),
+			"--exclude" => self.filter.exclude.push(option_value(name, inline, args)?),
+			"-j" | "--jobs" => self.jobs = number(name, inline, args)?.max(1),
+			"-e" | "--encoding" => self.encoding = Some(option_value(name, inline, args)?.parse()?),
+			"--markup" => self.markup = Some(option_value(name, inline, args)?.parse()?),
+			"-c" | "--compare" => self.compare = flag(name, inline)?,
+			"-w" | "--watch" => self.watch = flag(name, inline)?,
+			"--interval" => self.interval = number(name, inline, args)?.max(1) as u64,
+			"--reduce" => self.options.words.reduction = option_value(name, inline, args)?.parse()?,
+			"--histogram" => self.histogram = flag(name, inline)?,
+			"--lines" => self.lines = flag(name, inline)?,
+			"--max-width" => self.options.max_line_width = Some(number(name, inline, args)?),
+			"--lint" => self.lint = flag(name, inline)?,
+			"--ascending" => {
+				self.order = if flag(name, inline)? { Order::Ascending } else { Order::Descending };
+			}
+			"-i" | "--ignore-case" => self.options.words.case_fold = flag(name, inline)?,
+			"-p" | "--strip-punctuation" => self.options.words.strip_punctuation = flag(name, inline)?,
+			"--normalize" => self.options.words.normalization = option_value(name, inline, args)?.parse()?,
+			"-l" | "--language" => match option_value(name, inline, args)?.as_str() {
+				"auto" => self.options.detect_language = true,
+				value => {
+					self.options.language = value.parse()?;
+					self.options.detect_language = false;
+				}
+			},
+			"--max-grade" => {
+				let value = option_value(name, inline, args)?;
+				match value.parse::<f64>() {
+					Ok(grade) => self.max_grade = Some(grade),
+					Err(_) => return Err(format!("invalid grade '{}'", value)),
+				}
+			}
+			"-s" | "--stop-words" => {
+				for code in option_value(name, inline, args)?.split(',') {
+					match code.trim() {
+						"auto" => self.options.auto_stop_words = true,
+						code => self.options.words.stop_words.add_builtin(code.parse::<Language>()?),
+					}
+				}
+			}
+			"--ngrams" => {
+				for size in option_value(name, inline, args)?.split(',') {
+					match size.trim().parse::<usize>() {
+						Ok(n) if n > 0 => self.options.ngrams.push(n),
+						_ => return Err(format!("invalid n-gram size '{}'", size)),
+					}
+				}
+			}
+			"--ngram-min" => self.options.ngram_min_count = number(name, inline, args)?,
+			"--stop-words-file" => {
+				let path = option_value(name, inline, args)?;
+				if let Err(error) = self.options.words.stop_words.add_file(Path::new(&path)) {
+					return Err(format!("failed to read the stop words file '{}': {}", path, error));
+				}
+			}
+			"-k" | "--keywords" => self.keywords = Some(number(name, inline, args)?),
+			"--idf" => {
+				let path = option_value(name, inline, args)?;
+				match Idf::load(Path::new(&path)) {
+					Ok(idf) => self.idf = Some(idf),
+					Err(error) => return Err(format!("failed to read the IDF table '{}': {}", path, error)),
+				}
+			}
+			"--save-idf" => self.save_idf = Some(PathBuf::from(option_value(name, inline, args)?)),
+			"--terms" => {
+				let path = option_value(name, inline, args)?;
+				match WordsMatching::from_file(Path::new(&path)) {
+					Ok(metric) => self.options.metrics.push(Box::new(metric)),
+					Err(error) => return Err(format!("failed to read the terms file '{}': {}", path, error)),
+				}
+			}
+			"--spell" => {
+				let path = option_value(name, inline, args)?;
+				let dictionary = Arc::make_mut(self.options.dictionary.get_or_insert_with(Arc::default));
+				if let Err(error) = dictionary.add_file(Path::new(&path)) {
+					return Err(format!("failed to read the dictionary '{}': {}", path, error));
+				}
+			}
+			"--style" => self.style = flag(name, inline)?,
+			"--style-rules" => {
+				let path = option_value(name, inline, args)?;
+				if let Err(error) = self.style_rules.add_file(Path::new(&path)) {
+					return Err(format!("failed to read the style rules '{}': {}", path, error));
+				}
+			}
+			"--style-rule" => {
+				let value = option_value(name, inline, args)?;
+				let (rule, value) = value.split_once('=').ok_or(format!("expected RULE=VALUE for '{}'", name))?;
+				self.style_rules.set(rule.trim(), value.trim())?;
+			}
+			"--spell-ignore" => self.ignore_lists.push(PathBuf::from(option_value(name, inline, args)?)),
+			// Read before the other options, see `load_configs`.
+			"--config" => {
+				option_value(name, inline, args)?;
+			}
+			"--no-config" => (),
+			_ => return Err(format!("unknown option '{}'", name)),
+		}
+		Ok(())
+	}
+
+	/// Adds the ignore lists to the dictionary, once it is complete.
+	fn load_ignore_lists(&mut self) -> Result<(), String> {
+		let Some(dictionary) = self.options.dictionary.as_mut() else {
+			return Ok(());
+		};
+		let local = Path::new(".spelling");
+		if local.is_file() && !self.ignore_lists.iter().any(|path| path == local) {
+			self.ignore_lists.push(local.to_path_buf());
+		}
+		for path in self.ignore_lists.drain(..) {
+			if let Err(error) = Arc::make_mut(dictionary).add_file(&path) {
+				return Err(format!("failed to read the ignore list '{}': {}", path.display(), error));
+			}
+		}
+		Ok(())
+	}
+
+	/// Sets the options of a configuration file. Every key is the name of a
+	/// long option, with the value it is given on the command line; a list
+	/// stands for the option repeated. The keys of the `[style]` table are
+	/// style rules, which only apply with `style = true` or `--style`.
+	pub fn apply_config(&mut self, config: &Config) -> Result<(), String> {
+		for entry in &config.entries {
+			let at = |message: String| format!("{}:{}: {}", config.path.display(), entry.line, message);
+			let key = entry.key.replace('_', "-");
+			let (name, values) = match entry.table.as_str() {
+				"" if NOT_CONFIGURABLE.contains(&key.as_str()) => {
+					return Err(at(format!("'{}' cannot be set in a configuration file", key)));
+				}
+				"" => {
+					let values = match &entry.value {
+						Setting::List(items) if !LIST_OPTIONS.contains(&key.as_str()) => {
+							items.iter().map(Setting::to_string).collect()
+						}
+						value => vec![value.to_string()],
+					};
+					(format!("--{}", key), values)
+				}
+				"style" => ("--style-rule".to_string(), vec![format!("{}={}", key, entry.value)]),
+				table => return Err(at(format!("unknown table '[{}]'", table))),
+			};
+			for value in values {
+				let value = match PATH_OPTIONS.contains(&key.as_str()) {
+					true => config.dir().join(value).display().to_string(),
+					false => value,
+				};
+				self.set(&name, Some(&value), &mut std::iter::empty()).map_err(at)?;
+			}
+			if let Some(list) = list_of(&name).filter(|list| !self.configured.contains(list)) {
+				self.configured.push(list);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// The list the option `name` adds to, for the options which can be
+/// repeated or given several values.
+fn list_of(name: &str) -> Option<&'static str> {
+	match name {
+		"--include" => Some("include"),
+		"--exclude" => Some("exclude"),
+		"-s" | "--stop-words" | "--stop-words-file" => Some("stop-words"),
+		"--ngrams" => Some("ngrams"),
+		"--terms" => Some("terms"),
+		"--spell" => Some("spell"),
+		"--spell-ignore" => Some("spell-ignore"),
+		_ => None,
+	}
+}
+
+/// Options whose value is a path, relative to the configuration file it is
+/// set in.
+const PATH_OPTIONS: &[&str] = &["idf", "save-idf", "stop-words-file", "terms", "spell", "spell-ignore", "style-rules"];
+/// Options given a list separated by commas.
+const LIST_OPTIONS: &[&str] = &["stop-words", "ngrams"];
+const NOT_CONFIGURABLE: &[&str] = &["help", "config", "no-config"];
+
+/// The configuration files read before the command line `args`: the one
+/// given with `--config`, none with `--no-config`, or else the ones found
+/// from the working directory by [`config::discover`].
+pub fn load_configs(args: &[String]) -> Result<Vec<Config>, String> {
+	let mut paths = None;
+	let mut args = args.iter().take_while(|arg| *arg != "--");
+	while let Some(arg) = args.next() {
+		match arg.split_once('=') {
+			Some(("--config", path)) => paths = Some(vec![PathBuf::from(path)]),
+			_ if arg == "--config" => paths = Some(args.next().map(PathBuf::from).into_iter().collect()),
+			_ if arg == "--no-config" => paths = Some(Vec::new()),
+			_ => (),
+		}
+	}
+	let paths = match paths {
+		Some(paths) => paths,
+		None => config::discover(&env::current_dir().unwrap_or_default()),
+	};
+	paths
+		.iter()
+		.map(|path| {
+			Config::load(path).map_err(|error| format!("failed to read the configuration file '{}': {}", path.display(), error))
+		})
+		.collect()
+}
+
+fn walk_dir(dir: &Path, filter: &Filter, inputs: &mut Vec<Input>, errors: &mut Vec<Error>) {
+	match corpus::walk(dir, filter) {
+		Ok(files) => {
+			if files.is_empty() {
+				errors.push(Error::EmptyInput(format!("No file found in '{}'", dir.display())));
+			}
+			inputs.extend(files.into_iter().map(Input::File));
+		}
+		Err(error) => errors.push(Error::from_io(&dir.display().to_string(), error)),
+	}
+}
+
+fn expand_pattern(path: &Path) -> Vec<PathBuf> {
+	let pattern = match path.file_name() {
+		Some(name) => name.to_string_lossy().to_string(),
+		None => return Vec::new(),
+	};
+	let dir = match path.parent() {
+		Some(parent) if !parent.as_os_str().is_empty() => parent,
+		_ => Path::new("."),
+	};
+	let mut matches: Vec<PathBuf> = match read_dir(dir) {
+		Ok(entries) => entries
+			.filter_map(|entry| entry.ok())
+			.filter(|entry| glob_match(&pattern, &entry.file_name().to_string_lossy()))
+			.map(|entry| entry.path())
+			.collect(),
+		Err(_) => Vec::new(),
+	};
+	matches.sort();
+	matches
+}
+
+/// Turns the raw paths given on the command line into the list of inputs
+/// to analyse. Directories are walked recursively, keeping the files
+/// accepted by `filter`, and patterns are expanded; invalid paths are
+/// skipped and returned as errors.
+pub fn collect_inputs(
+	paths: &[String],
+	filter: &Filter,
+	check_path: fn(&Path) -> Result<(), Error>,
+) -> (Vec<Input>, Vec<Error>) {
+	let mut inputs = Vec::new();
+	let mut errors = Vec::new();
+
+	for raw in paths {
+		if raw == "-" {
+			inputs.push(Input::Stdin);
+			continue;
+		}
+		let path = Path::new(raw);
+		if !path.exists() && raw.contains(['*', '?']) {
+			let matches = expand_pattern(path);
+			if matches.is_empty() {
+				errors.push(Error::EmptyInput(format!("No file matches '{}'", raw)));
+			}
+			for found in matches {
+				if found.is_dir() {
+					walk_dir(&found, filter, &mut inputs, &mut errors);
+				} else {
+					match check_path(&found) {
+						Ok(()) => inputs.push(Input::File(found)),
+						Err(error) => errors.push(error),
+					}
+				}
+			}
+		} else if path.is_dir() {
+			walk_dir(path, filter, &mut inputs, &mut errors);
+		} else {
+			match check_path(path) {
+				Ok(()) => inputs.push(Input::File(path.to_path_buf())),
+				Err(error) => errors.push(error),
+			}
+		}
+	}
+	(inputs, errors)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// The options of the configuration file `config`, then of the command
+	/// line.
+	fn args(config: &str, command_line: &[&str]) -> Args {
+		let config = Config { path: PathBuf::from("text_analyser.toml"), entries: Config::parse(config).unwrap() };
+		let mut args = Args::new();
+		args.apply_config(&config).unwrap();
+		args.read(command_line.iter().map(|arg| arg.to_string())).unwrap();
+		args
+	}
+
+	#[test]
+	fn command_line_wins() {
+		assert_eq!(args("top = 20", &[]).top, Some(20));
+		assert_eq!(args("top = 20", &["-n", "5"]).top, Some(5));
+		assert!(args("lines = true", &[]).lines);
+		assert!(!args("lines = true", &["--lines=false"]).lines);
+		assert!(matches!(args("format = \"json\"", &["--format=csv"]).format, Format::Csv));
+	}
+
+	#[test]
+	fn command_line_replaces_lists() {
+		assert_eq!(args("ngrams = [2]", &[]).options.ngrams, vec![2]);
+		assert_eq!(args("ngrams = [2]", &["--ngrams", "3"]).options.ngrams, vec![3]);
+		assert_eq!(args("ngrams = [2]", &["--ngrams", "3", "--ngrams=4,5"]).options.ngrams, vec![3, 4, 5]);
+		assert_eq!(args("include = [\"*.md\", \"*.txt\"]", &[]).filter.include, vec!["*.md", "*.txt"]);
+		assert_eq!(args("include = [\"*.md\"]", &["--include", "*.rs"]).filter.include, vec!["*.rs"]);
+
+		let stop_words = &args("stop-words = [\"en\"]", &[]).options.words.stop_words;
+		assert!(stop_words.contains("the"));
+		let stop_words = &args("stop-words = [\"en\"]", &["-s", "fr"]).options.words.stop_words;
+		assert!(stop_words.contains("le") && !stop_words.contains("the"));
+	}
+
+	#[test]
+	fn style_table_only_tunes_the_rules() {
+		assert!(args("[style]\nlong-sentence = 3", &[]).options.style.is_none());
+
+		let rules = args("[style]\nlong-sentence = 3", &["--style"]).options.style.unwrap();
+		assert_eq!(rules.max_sentence_words, 3);
+		let rules = args("style = true\n[style]\nlong-sentence = 3", &[]).options.style.unwrap();
+		assert_eq!(rules.max_sentence_words, 3);
+		assert!(args("style = true", &["--style=false"]).options.style.is_none());
+
+		let rules = args("[style]\nlong-sentence = 3", &["--style-rule", "adverbs=off"]).options.style.unwrap();
+		assert!(!rules.adverbs && rules.max_sentence_words == 3);
+	}
+}
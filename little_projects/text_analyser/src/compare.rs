@@ -0,0 +1,89 @@
+
+use std::cmp::Ordering;
+use crate::{frequency::FrequencyTable, readability::Readability, Stats};
+
+/// How close the vocabularies of two texts are.
+pub struct Comparison {
+	/// Words used by both texts, the most used first.
+	pub shared: Vec<String>,
+	/// Words of the first text only, the most used first.
+	pub only_left: Vec<String>,
+	/// Words of the second text only, the most used first.
+	pub only_right: Vec<String>,
+	/// Shared words over all the distinct words, from 0 to 1.
+	pub jaccard: f64,
+	/// Cosine of the angle between the word frequency vectors, from 0
+	/// (nothing in common) to 1 (same proportions of every word).
+	pub cosine: f64,
+}
+
+fn sort_by_use(words: &mut [(String, usize)]) -> Vec<String> {
+	words.sort_by(|a, b| match b.1.cmp(&a.1) {
+		Ordering::Equal => a.0.cmp(&b.0),
+		other => other,
+	});
+	words.iter().map(|(word, _)| word.clone()).collect()
+}
+
+pub fn compare(left: &FrequencyTable, right: &FrequencyTable) -> Comparison {
+	let mut shared = Vec::new();
+	let mut only_left = Vec::new();
+	let mut only_right = Vec::new();
+	let mut dot = 0.0;
+
+	for (word, count) in left.iter() {
+		match right.count(word) {
+			0 => only_left.push((word.clone(), count)),
+			other => {
+				dot += count as f64 * other as f64;
+				shared.push((word.clone(), count + other));
+			}
+		}
+	}
+	for (word, count) in right.iter() {
+		if left.count(word) == 0 {
+			only_right.push((word.clone(), count));
+		}
+	}
+
+	let norm = |table: &FrequencyTable| table.iter().map(|(_, count)| (count * count) as f64).sum::<f64>().sqrt();
+	let norms = norm(left) * norm(right);
+	let union = shared.len() + only_left.len() + only_right.len();
+
+	Comparison {
+		jaccard: if union == 0 { 0.0 } else { shared.len() as f64 / union as f64 },
+		cosine: if norms == 0.0 { 0.0 } else { dot / norms },
+		shared: sort_by_use(&mut shared),
+		only_left: sort_by_use(&mut only_left),
+		only_right: sort_by_use(&mut only_right),
+	}
+}
+
+/// Every figure of `stats` under a readable name, in the order they are
+/// shown side by side. Readability indices are NaN for a text without
+/// words.
+pub fn metrics(stats: &Stats) -> Vec<(&'static str, f64)> {
+	let readability = stats.readability();
+	let index = |f: fn(&Readability) -> f64| readability.as_ref().map(f).unwrap_or(f64::NAN);
+
+	vec![
+		("characters", stats.chars() as f64),
+		("code points", stats.code_points() as f64),
+		("bytes", stats.bytes() as f64),
+		("words", stats.words() as f64),
+		("distinct words", stats.frequencies().distinct() as f64),
+		("phrases", stats.phrases() as f64),
+		("non-empty lines", stats.lines() as f64),
+		("syllables", stats.syllables() as f64),
+		("average word len", stats.word_len()),
+		("median word len", stats.word_lengths().median()),
+		("average phrase len", stats.phrases_len()),
+		("median phrase len", stats.phrase_lengths().median()),
+		("longest phrase", stats.max_phrase_len() as f64),
+		("most used count", stats.most_used_count() as f64),
+		("flesch reading ease", index(|r| r.flesch_reading_ease)),
+		("flesch-kincaid grade", index(|r| r.flesch_kincaid_grade)),
+		("gunning fog", index(|r| r.gunning_fog)),
+		("smog", index(|r| r.smog)),
+	]
+}
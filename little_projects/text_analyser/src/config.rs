@@ -0,0 +1,366 @@
+
+use std::{
+	env, fmt,
+	fs::read_to_string,
+	io,
+	path::{Path, PathBuf},
+};
+
+/// Names of a project configuration file, looked for from the working
+/// directory upwards.
+pub const FILE_NAMES: [&str; 2] = ["text_analyser.toml", ".text_analyser.toml"];
+
+/// A value of a configuration file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Setting {
+	Bool(bool),
+	Int(i64),
+	Float(f64),
+	Str(String),
+	List(Vec<Setting>),
+}
+
+impl fmt::Display for Setting {
+	/// The value as written on the command line; the items of a list are
+	/// separated by commas.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Setting::Bool(value) => write!(f, "{}", value),
+			Setting::Int(value) => write!(f, "{}", value),
+			Setting::Float(value) => write!(f, "{}", value),
+			Setting::Str(value) => write!(f, "{}", value),
+			Setting::List(items) => {
+				let items: Vec<String> = items.iter().map(Setting::to_string).collect();
+				write!(f, "{}", items.join(","))
+			}
+		}
+	}
+}
+
+/// A `key = value` line of a configuration file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+	/// Name of the `[table]` the key is in, empty before the first one.
+	pub table: String,
+	pub key: String,
+	pub value: Setting,
+	/// Line number of the key, from 1.
+	pub line: usize,
+}
+
+/// A configuration file, in the subset of TOML made of tables and of keys
+/// with a string, a number, a boolean or an array of them.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+	pub path: PathBuf,
+	pub entries: Vec<Entry>,
+}
+
+struct Parser {
+	chars: Vec<char>,
+	at: usize,
+	line: usize,
+}
+
+impl Parser {
+	fn peek(&self) -> Option<char> {
+		self.chars.get(self.at).copied()
+	}
+
+	fn next(&mut self) -> Option<char> {
+		let c = self.peek()?;
+		self.at += 1;
+		if c == '\n' {
+			self.line += 1;
+		}
+		Some(c)
+	}
+
+	fn error(&self, message: &str) -> String {
+		format!("{} at line {}", message, self.line)
+	}
+
+	/// Skips spaces and tabs, and line breaks and comments with `lines`.
+	fn skip_blank(&mut self, lines: bool) {
+		while let Some(c) = self.peek() {
+			match c {
+				' ' | '\t' => (),
+				'\r' | '\n' if lines => (),
+				'#' if lines => {
+					while self.peek().is_some_and(|c| c != '\n') {
+						self.next();
+					}
+					continue;
+				}
+				_ => return,
+			}
+			self.next();
+		}
+	}
+
+	/// Expects the end of the line, after an optional comment.
+	fn end_of_line(&mut self) -> Result<(), String> {
+		self.skip_blank(false);
+		if self.peek() == Some('#') {
+			while self.peek().is_some_and(|c| c != '\n') {
+				self.next();
+			}
+		}
+		match self.peek() {
+			None | Some('\n') => Ok(()),
+			Some('\r') if self.chars.get(self.at + 1) == Some(&'\n') => Ok(()),
+			Some(_) => Err(self.error("expected the end of the line")),
+		}
+	}
+
+	fn key(&mut self) -> Result<String, String> {
+		match self.peek() {
+			Some('"') | Some('\'') => self.string(),
+			_ => {
+				let mut key = String::new();
+				while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_') {
+					key.push(c);
+					self.next();
+				}
+				if key.is_empty() {
+					return Err(self.error("expected a key"));
+				}
+				Ok(key)
+			}
+		}
+	}
+
+	fn string(&mut self) -> Result<String, String> {
+		let quote = self.next().unwrap_or('"');
+		let mut text = String::new();
+		loop {
+			// The line break is left for the error to be on the line of the
+			// string.
+			let Some(c) = self.peek().filter(|c| *c != '\n') else {
+				return Err(self.error("unterminated string"));
+			};
+			self.next();
+			match c {
+				c if c == quote => return Ok(text),
+				'\\' if quote == '"' => {
+					if self.peek() == Some('\n') {
+						return Err(self.error("invalid escape"));
+					}
+					let escaped = match self.next() {
+						Some('n') => '\n',
+						Some('t') => '\t',
+						Some('r') => '\r',
+						Some('"') => '"',
+						Some('\\') => '\\',
+						Some('u') => {
+							let code: String = (0..4).filter_map(|_| self.next()).collect();
+							u32::from_str_radix(&code, 16)
+								.ok()
+								.and_then(char::from_u32)
+								.ok_or_else(|| self.error("invalid unicode escape"))?
+						}
+						_ => return Err(self.error("invalid escape")),
+					};
+					text.push(escaped);
+				}
+				c => text.push(c),
+			}
+		}
+	}
+
+	fn value(&mut self) -> Result<Setting, String> {
+		match self.peek() {
+			Some('"') | Some('\'') => Ok(Setting::Str(self.string()?)),
+			Some('[') => {
+				self.next();
+				let mut items = Vec::new();
+				loop {
+					self.skip_blank(true);
+					if self.peek() == Some(']') {
+						self.next();
+						return Ok(Setting::List(items));
+					}
+					items.push(self.value()?);
+					self.skip_blank(true);
+					match self.next() {
+						Some(',') => (),
+						Some(']') => return Ok(Setting::List(items)),
+						_ => return Err(self.error("expected ',' or ']' in an array")),
+					}
+				}
+			}
+			_ => {
+				let mut word = String::new();
+				while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric() || "+-._".contains(*c)) {
+					word.push(c);
+					self.next();
+				}
+				let number = word.replace('_', "");
+				match word.as_str() {
+					"" => Err(self.error("expected a value")),
+					"true" => Ok(Setting::Bool(true)),
+					"false" => Ok(Setting::Bool(false)),
+					_ => number
+						.parse()
+						.map(Setting::Int)
+						.or_else(|_| number.parse().map(Setting::Float))
+						.map_err(|_| self.error(&format!("invalid value '{}'", word))),
+				}
+			}
+		}
+	}
+}
+
+impl Config {
+	pub fn parse(text: &str) -> Result<Vec<Entry>, String> {
+		let mut parser = Parser { chars: text.chars().collect(), at: 0, line: 1 };
+		let mut entries = Vec::new();
+		let mut table = String::new();
+		loop {
+			parser.skip_blank(true);
+			match parser.peek() {
+				None => return Ok(entries),
+				Some('[') => {
+					parser.next();
+					if parser.peek() == Some('[') {
+						return Err(parser.error("arrays of tables are not supported"));
+					}
+					parser.skip_blank(false);
+					table = parser.key()?;
+					parser.skip_blank(false);
+					if parser.peek() != Some(']') {
+						return Err(parser.error("expected ']' after the table name"));
+					}
+					parser.next();
+				}
+				Some(_) => {
+					let line = parser.line;
+					let key = parser.key()?;
+					parser.skip_blank(false);
+					if parser.peek() != Some('=') {
+						return Err(parser.error(&format!("expected '=' after '{}'", key)));
+					}
+					parser.next();
+					parser.skip_blank(false);
+					let value = parser.value()?;
+					entries.push(Entry { table: table.clone(), key, value, line });
+				}
+			}
+			parser.end_of_line()?;
+		}
+	}
+
+	pub fn load(path: &Path) -> io::Result<Config> {
+		let text = read_to_string(path)?;
+		match Config::parse(&text) {
+			Ok(entries) => Ok(Config { path: path.to_path_buf(), entries }),
+			Err(message) => Err(io::Error::new(io::ErrorKind::InvalidData, message)),
+		}
+	}
+
+	/// Directory the relative paths of the file are resolved from.
+	pub fn dir(&self) -> &Path {
+		self.path.parent().unwrap_or(Path::new("."))
+	}
+}
+
+/// The user configuration file, `text_analyser/config.toml` in
+/// `$XDG_CONFIG_HOME`, or in `~/.config` when it is not set.
+pub fn user_file() -> Option<PathBuf> {
+	let dir = match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+		Some(dir) => PathBuf::from(dir),
+		None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+	};
+	Some(dir.join("text_analyser").join("config.toml"))
+}
+
+/// The nearest project configuration file, in `dir` or its parents.
+pub fn project_file(dir: &Path) -> Option<PathBuf> {
+	dir.ancestors().flat_map(|dir| FILE_NAMES.iter().map(move |name| dir.join(name))).find(|path| path.is_file())
+}
+
+/// The configuration files that apply in `dir`, the least specific first:
+/// the user file then the project file, those that exist.
+pub fn discover(dir: &Path) -> Vec<PathBuf> {
+	let mut files: Vec<PathBuf> = user_file().filter(|path| path.is_file()).into_iter().collect();
+	files.extend(project_file(dir));
+	files
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn entry(table: &str, key: &str, value: Setting, line: usize) -> Entry {
+		Entry { table: table.to_string(), key: key.to_string(), value, line }
+	}
+
+	#[test]
+	fn strings_and_escapes() {
+		let entries = Config::parse("a = \"tab\\there \\\"quoted\\\" \\\\ \\u00e9\"\nb = 'C:\\dir\\n'\n\"c d\" = \"\"").unwrap();
+		assert_eq!(entries, vec![
+			entry("", "a", Setting::Str("tab\there \"quoted\" \\ é".to_string()), 1),
+			entry("", "b", Setting::Str("C:\\dir\\n".to_string()), 2),
+			entry("", "c d", Setting::Str(String::new()), 3),
+		]);
+	}
+
+	#[test]
+	fn values() {
+		let entries = Config::parse("a = true\nb = false\nc = -12\nd = 1_000\ne = 2.5").unwrap();
+		let values: Vec<Setting> = entries.into_iter().map(|entry| entry.value).collect();
+		assert_eq!(values, vec![
+			Setting::Bool(true),
+			Setting::Bool(false),
+			Setting::Int(-12),
+			Setting::Int(1000),
+			Setting::Float(2.5),
+		]);
+	}
+
+	#[test]
+	fn arrays_across_lines() {
+		let entries = Config::parse("list = [\n\t\"en\", # English\n\t\"fr\",\n]\nnext = [ 1, [2, 3] ]\nempty = []").unwrap();
+		assert_eq!(entries, vec![
+			entry("", "list", Setting::List(vec![Setting::Str("en".to_string()), Setting::Str("fr".to_string())]), 1),
+			entry("", "next", Setting::List(vec![Setting::Int(1), Setting::List(vec![Setting::Int(2), Setting::Int(3)])]), 5),
+			entry("", "empty", Setting::List(Vec::new()), 6),
+		]);
+		assert_eq!(entries[0].value.to_string(), "en,fr");
+	}
+
+	#[test]
+	fn comments_and_tables() {
+		let text = "# a comment\ntop = 20 # after a value\n\n[style]  # after a table\nadverbs = 3\n[ other ]\nkey = \"# not a comment\"";
+		assert_eq!(Config::parse(text).unwrap(), vec![
+			entry("", "top", Setting::Int(20), 2),
+			entry("style", "adverbs", Setting::Int(3), 5),
+			entry("other", "key", Setting::Str("# not a comment".to_string()), 7),
+		]);
+	}
+
+	#[test]
+	fn crlf_line_breaks() {
+		let text = "top = 20\r\n# comment\r\nlist = [\r\n  1,\r\n  2\r\n]\r\n[style]\r\nadverbs = 3\r\n";
+		assert_eq!(Config::parse(text).unwrap(), vec![
+			entry("", "top", Setting::Int(20), 1),
+			entry("", "list", Setting::List(vec![Setting::Int(1), Setting::Int(2)]), 3),
+			entry("style", "adverbs", Setting::Int(3), 8),
+		]);
+	}
+
+	#[test]
+	fn errors_give_their_line() {
+		let error = |text: &str| Config::parse(text).unwrap_err();
+		assert_eq!(error("a = 1\nb =\n"), "expected a value at line 2");
+		assert_eq!(error("a = 1\nb 2"), "expected '=' after 'b' at line 2");
+		assert_eq!(error("a = 1\nb\nc = 2"), "expected '=' after 'b' at line 2");
+		assert_eq!(error("a = [\n1,\n2 3]"), "expected ',' or ']' in an array at line 3");
+		assert_eq!(error("\n\na = \"open\nb = 1"), "unterminated string at line 3");
+		assert_eq!(error("a = \"\\q\""), "invalid escape at line 1");
+		assert_eq!(error("a = 1 2"), "expected the end of the line at line 1");
+		assert_eq!(error("a = yes"), "invalid value 'yes' at line 1");
+		assert_eq!(error("[[tables]]"), "arrays of tables are not supported at line 1");
+		assert_eq!(error("[style\na = 1"), "expected ']' after the table name at line 1");
+	}
+}
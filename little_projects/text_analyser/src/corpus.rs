@@ -0,0 +1,225 @@
+
+use std::{
+	fs::read_dir,
+	io,
+	path::{Component, Path, PathBuf},
+	sync::{atomic::{AtomicUsize, Ordering}, Mutex},
+	thread,
+};
+
+/// Matches `name` against a pattern where `*` is any sequence of
+/// characters and `?` any single character.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+	let pattern: Vec<char> = pattern.chars().collect();
+	let name: Vec<char> = name.chars().collect();
+	let (mut p, mut n) = (0, 0);
+	let mut backtrack: Option<(usize, usize)> = None;
+
+	while n < name.len() {
+		if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+			p += 1;
+			n += 1;
+		} else if p < pattern.len() && pattern[p] == '*' {
+			backtrack = Some((p, n));
+			p += 1;
+		} else if let Some((star, matched)) = backtrack {
+			p = star + 1;
+			n = matched + 1;
+			backtrack = Some((star, matched + 1));
+		} else {
+			return false;
+		}
+	}
+	pattern[p..].iter().all(|c| *c == '*')
+}
+
+fn match_components(pattern: &[&str], path: &[String]) -> bool {
+	match pattern.split_first() {
+		None => path.is_empty(),
+		Some((&"**", rest)) => (0..=path.len()).any(|skip| match_components(rest, &path[skip..])),
+		Some((first, rest)) => match path.split_first() {
+			Some((name, path)) => glob_match(first, name) && match_components(rest, path),
+			None => false,
+		},
+	}
+}
+
+/// Matches a relative `path` against a pattern. A pattern without '/' is
+/// matched against the file name only; otherwise it is matched against the
+/// whole path, `**` standing for any number of directories.
+pub fn path_match(pattern: &str, path: &Path) -> bool {
+	let components: Vec<String> = path
+		.components()
+		.filter_map(|component| match component {
+			Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+			_ => None,
+		})
+		.collect();
+
+	if !pattern.contains('/') {
+		return match components.last() {
+			Some(name) => glob_match(pattern, name),
+			None => false,
+		};
+	}
+	let pattern: Vec<&str> = pattern.trim_start_matches("./").split('/').filter(|part| !part.is_empty()).collect();
+	match_components(&pattern, &components)
+}
+
+/// Which files of a directory tree make the corpus.
+#[derive(Clone, Default)]
+pub struct Filter {
+	/// Patterns of the files to analyse, every file when empty.
+	pub include: Vec<String>,
+	/// Patterns of the files and directories to leave out.
+	pub exclude: Vec<String>,
+}
+
+impl Filter {
+	fn excludes(&self, relative: &Path) -> bool {
+		self.exclude.iter().any(|pattern| path_match(pattern, relative))
+	}
+
+	/// Whether the file at `relative` (to the walked directory) is part of
+	/// the corpus.
+	pub fn accepts(&self, relative: &Path) -> bool {
+		(self.include.is_empty() || self.include.iter().any(|pattern| path_match(pattern, relative)))
+			&& !self.excludes(relative)
+	}
+}
+
+fn walk_dir(root: &Path, dir: &Path, filter: &Filter, files: &mut Vec<PathBuf>) -> io::Result<()> {
+	let mut entries: Vec<(PathBuf, bool)> = read_dir(dir)?
+		.filter_map(|entry| entry.ok())
+		.map(|entry| (entry.path(), entry.file_type().is_ok_and(|kind| kind.is_symlink())))
+		.collect();
+	entries.sort();
+
+	for (path, link) in entries {
+		let relative = path.strip_prefix(root).unwrap_or(&path);
+		if path.is_dir() {
+			// A link to a directory can lead back to one of its parents: only
+			// the real directories are walked.
+			if link {
+				continue;
+			}
+			// A directory that cannot be read is skipped like an excluded one.
+			if !filter.excludes(relative) {
+				let _ = walk_dir(root, &path, filter, files);
+			}
+		} else if path.is_file() && filter.accepts(relative) {
+			files.push(path);
+		}
+	}
+	Ok(())
+}
+
+/// The files of the tree under `root` accepted by `filter`, sorted.
+pub fn walk(root: &Path, filter: &Filter) -> io::Result<Vec<PathBuf>> {
+	let mut files = Vec::new();
+	walk_dir(root, root, filter, &mut files)?;
+	Ok(files)
+}
+
+/// Number of threads to use when none is asked for.
+pub fn default_jobs() -> usize {
+	thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Applies `f` to every item on `jobs` threads and returns the results in
+/// the order of the items.
+pub fn parallel_map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+	T: Sync,
+	R: Send,
+	F: Fn(&T) -> R + Sync,
+{
+	let jobs = jobs.clamp(1, items.len().max(1));
+	if jobs == 1 {
+		return items.iter().map(f).collect();
+	}
+
+	let next = AtomicUsize::new(0);
+	let results: Mutex<Vec<Option<R>>> = Mutex::new((0..items.len()).map(|_| None).collect());
+	thread::scope(|scope| {
+		for _ in 0..jobs {
+			scope.spawn(|| loop {
+				let i = next.fetch_add(1, Ordering::Relaxed);
+				if i >= items.len() {
+					break;
+				}
+				let result = f(&items[i]);
+				results.lock().expect("a thread panicked while storing its result")[i] = Some(result);
+			});
+		}
+	});
+	results
+		.into_inner()
+		.expect("a thread panicked while storing its result")
+		.into_iter()
+		.map(|result| result.expect("every item has a result"))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{env, fs, process};
+
+	#[test]
+	fn glob_patterns() {
+		assert!(glob_match("*.md", "notes.md"));
+		assert!(glob_match("*", ""));
+		assert!(glob_match("a?c", "abc"));
+		assert!(glob_match("*a*b", "xxaxxab"));
+		assert!(!glob_match("*.md", "notes.mdx"));
+		assert!(!glob_match("a?c", "ac"));
+		assert!(glob_match("été*", "été.txt"));
+	}
+
+	#[test]
+	fn path_patterns() {
+		assert!(path_match("*.md", Path::new("docs/guide/intro.md")));
+		assert!(!path_match("docs/*.md", Path::new("docs/guide/intro.md")));
+		assert!(path_match("docs/**/*.md", Path::new("docs/guide/intro.md")));
+		assert!(path_match("docs/**/*.md", Path::new("docs/intro.md")));
+		assert!(path_match("./docs/*.md", Path::new("docs/intro.md")));
+		assert!(!path_match("**/target", Path::new("src/target.rs")));
+		assert!(path_match("**/target", Path::new("a/b/target")));
+	}
+
+	#[test]
+	fn filters() {
+		let filter = Filter { include: vec!["*.md".to_string()], exclude: vec!["drafts/**".to_string()] };
+		assert!(filter.accepts(Path::new("notes/a.md")));
+		assert!(!filter.accepts(Path::new("notes/a.txt")));
+		assert!(!filter.accepts(Path::new("drafts/a.md")));
+		assert!(Filter::default().accepts(Path::new("anything")));
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn walk_skips_linked_directories() {
+		let root = env::temp_dir().join(format!("text_analyser_walk_{}", process::id()));
+		let _ = fs::remove_dir_all(&root);
+		fs::create_dir_all(root.join("sub/skipped")).unwrap();
+		fs::write(root.join("a.txt"), "a").unwrap();
+		fs::write(root.join("sub/b.txt"), "b").unwrap();
+		fs::write(root.join("sub/skipped/c.txt"), "c").unwrap();
+		std::os::unix::fs::symlink("..", root.join("sub/loop")).unwrap();
+		std::os::unix::fs::symlink("b.txt", root.join("sub/link.txt")).unwrap();
+
+		let filter = Filter { include: Vec::new(), exclude: vec!["skipped".to_string()] };
+		let files = walk(&root, &filter);
+		fs::remove_dir_all(&root).unwrap();
+		let files: Vec<PathBuf> = files.unwrap().iter().map(|file| file.strip_prefix(&root).unwrap().to_path_buf()).collect();
+		assert_eq!(files, vec![PathBuf::from("a.txt"), PathBuf::from("sub/b.txt"), PathBuf::from("sub/link.txt")]);
+	}
+
+	#[test]
+	fn parallel_map_keeps_the_order() {
+		let items: Vec<usize> = (0..100).collect();
+		assert_eq!(parallel_map(&items, 4, |n| n * 2), items.iter().map(|n| n * 2).collect::<Vec<usize>>());
+		assert_eq!(parallel_map(&[] as &[usize], 4, |n| *n), Vec::<usize>::new());
+	}
+}
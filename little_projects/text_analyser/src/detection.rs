@@ -0,0 +1,111 @@
+
+use std::collections::HashMap;
+use crate::language::Language;
+
+/// The most frequent character trigrams of each language, the most frequent
+/// first. Words are lowercased and surrounded by '_'.
+const ENGLISH: &str = "
+_th the he_ _an and nd_ _of of_ _to to_ ing ng_ _in in_ ed_ er_ _a_ ion tio is_
+_is re_ on_ es_ at_ ent hat tha _co _be _re for _fo or_ ly_ _wh ter _it it_ _ha
+st_ ati ere her _wa as_ al_ _wi wit ith th_ _on _st _pr ver all _se _ma _ne ons
+ce_ _ca _so _he _we our you _yo are nt_ ts_ _fr thi _di ght igh rs_ his _hi hav
+ave ve_ ate _wo _mo ess men nce _us ll_ _as _at _or _by by_ whi hic
+ich _sh wh_ _al _ex int ry_ ow_ _ho how ity ty_ _ou out _up ore ers _lo _kn
+";
+
+const FRENCH: &str = "
+_de de_ es_ _le le_ ent _la la_ _et et_ nt_ les _un ion tio _qu que ue_ _pa re_
+_co _pr on_ _da dan ans ns_ _re _des des our _po pou ur_ _en en_ men ai_ _pl _il
+il_ _ne _se est _es st_ _au au_ ait _ét été té_ _à_ _du du_ eur par ne_ ons ont
+qui _ce ce_ _une une _pe ous _vo _no _so lle ell _ma _mo ier ère ès_ _ê êtr tre
+ais eux ux_ _sa _su sur son _av avo voi oir ir_ _fa fai _ch ien _bi
+bie _tr tou out _to ité _ça ça_ _là _où où_ _mê mêm ême eme ées ée_ _on
+";
+
+const SPANISH: &str = "
+_de de_ _la la_ os_ _el el_ _qu que ue_ _en en_ es_ as_ _lo los _co ent ón_ ión
+ció aci _se _y_ _a_ ado do_ _pa _un _po _es est _al al_ ra_ _re _pr con on_ _su
+_ha _no ara par ada _ma nte _me _di _si _ta men ien _pe res _to to_ ero ía_ _mu
+muy uy_ _ot otr _añ año ño_ _má más ás_ _tr _ca _ci _ll _hi _ti _ve _pu ida dad
+ad_ _cu _do _fu era _ya ya_ _so _te hab aba ba_ _nu _lu _ah ahí _vi _ná
+";
+
+const GERMAN: &str = "
+_de der er_ en_ _di die ie_ ch_ ich sch _un und nd_ _ei ein ine _ge _be _zu zu_
+_da den _in in_ ter ung ng_ ten _ni cht _si sie _au _mi mit it_ _ve ver _is ist
+st_ _es _wi _so _we _ha _fü für ür_ ach auf uf_ _an ber eit _vo che das as_ gen
+_ma _no ßen _gr _sc _üb übe _kö kön _mü _wü _sp _st _wa war _ab nde
+_ja hen lic _ke kei sse ss_ _je ehr _ne nen nic erd rde
+";
+
+/// Least number of trigrams of a text for its language to be guessed.
+const MIN_TRIGRAMS: usize = 20;
+/// Least confidence for a guess to be given.
+const MIN_CONFIDENCE: f64 = 0.1;
+
+/// The language of a text, and how sure the detector is of it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Detection {
+	pub language: Language,
+	/// How far ahead of the runner-up the language is, from 0 (a tie) to 1
+	/// (no trigram of another language).
+	pub confidence: f64,
+}
+
+fn profile(language: Language) -> HashMap<&'static str, usize> {
+	let list = match language {
+		Language::English => ENGLISH,
+		Language::French => FRENCH,
+		Language::Spanish => SPANISH,
+		Language::German => GERMAN,
+	};
+	let mut ranks = HashMap::new();
+	for trigram in list.split_whitespace() {
+		let rank = ranks.len();
+		ranks.entry(trigram).or_insert(rank);
+	}
+	ranks
+}
+
+/// Counts the character trigrams of the words of `text`.
+fn trigrams(text: &str) -> HashMap<String, usize> {
+	let mut counts = HashMap::new();
+	for word in text.split(|c: char| !c.is_alphabetic()).filter(|word| !word.is_empty()) {
+		let chars: Vec<char> = format!("_{}_", word.to_lowercase()).chars().collect();
+		for window in chars.windows(3) {
+			*counts.entry(window.iter().collect()).or_insert(0) += 1;
+		}
+	}
+	counts
+}
+
+/// Guesses the language of `text` from its character trigrams, `None`
+/// for a text too short to tell or which looks like none of the bundled
+/// profiles better than the others.
+pub fn detect(text: &str) -> Option<Detection> {
+	let counts = trigrams(text);
+	if counts.values().sum::<usize>() < MIN_TRIGRAMS {
+		return None;
+	}
+	let mut scores: Vec<(Language, f64)> = Language::ALL
+		.iter()
+		.map(|language| {
+			let ranks = profile(*language);
+			let len = ranks.len() as f64;
+			// Frequent trigrams of the language weigh more than rare ones.
+			let score = counts
+				.iter()
+				.filter_map(|(trigram, count)| ranks.get(trigram.as_str()).map(|rank| *count as f64 * (1.0 - *rank as f64 / len / 2.0)))
+				.sum();
+			(*language, score)
+		})
+		.collect();
+
+	scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+	match scores[..] {
+		[(language, best), (_, second), ..] if best > 0.0 => {
+			Some(Detection { language, confidence: 1.0 - second / best }).filter(|detection| detection.confidence >= MIN_CONFIDENCE)
+		}
+		_ => None,
+	}
+}
@@ -0,0 +1,26 @@
+
+use std::fmt;
+
+/// A problem found at a position of a text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+	/// Line number, from 1.
+	pub line: usize,
+	/// Column in user-perceived characters, from 1.
+	pub column: usize,
+	/// Name of the check that found the problem, like "trailing-whitespace".
+	pub rule: &'static str,
+	pub message: String,
+}
+
+impl Diagnostic {
+	pub fn new(line: usize, column: usize, rule: &'static str, message: String) -> Diagnostic {
+		Diagnostic { line, column, rule, message }
+	}
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}:{}: {} [{}]", self.line, self.column, self.message, self.rule)
+	}
+}
@@ -0,0 +1,334 @@
+
+use std::{char::REPLACEMENT_CHARACTER, fmt, io, str::{self, FromStr}};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+	Utf8,
+	Utf16Le,
+	Utf16Be,
+	Latin1,
+	Windows1252,
+}
+
+impl fmt::Display for Encoding {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Encoding::Utf8 => "utf-8",
+			Encoding::Utf16Le => "utf-16le",
+			Encoding::Utf16Be => "utf-16be",
+			Encoding::Latin1 => "iso-8859-1",
+			Encoding::Windows1252 => "windows-1252",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+impl FromStr for Encoding {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().replace('_', "-").as_str() {
+			"utf-8" | "utf8" => Ok(Encoding::Utf8),
+			"utf-16le" | "utf16le" => Ok(Encoding::Utf16Le),
+			"utf-16be" | "utf16be" => Ok(Encoding::Utf16Be),
+			"iso-8859-1" | "latin-1" | "latin1" => Ok(Encoding::Latin1),
+			"windows-1252" | "cp1252" => Ok(Encoding::Windows1252),
+			_ => Err(format!(
+				"unknown encoding '{}' (expected utf-8, utf-16le, utf-16be, latin-1 or windows-1252)",
+				s
+			)),
+		}
+	}
+}
+
+/// Characters of the bytes 0x80 to 0x9F in windows-1252; the five unused
+/// bytes keep their latin-1 control character.
+const WINDOWS_1252: [char; 32] = [
+	'€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+	'\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+/// Finds the encoding of a text from its first bytes: a byte order mark
+/// first, then valid UTF-8, then the zero bytes UTF-16 gives to ASCII
+/// characters, windows-1252 otherwise. Returns the encoding and the length
+/// of the byte order mark to skip.
+pub fn detect(sample: &[u8]) -> (Encoding, usize) {
+	if sample.starts_with(&[0xEF, 0xBB, 0xBF]) {
+		return (Encoding::Utf8, 3);
+	}
+	if sample.starts_with(&[0xFF, 0xFE]) {
+		return (Encoding::Utf16Le, 2);
+	}
+	if sample.starts_with(&[0xFE, 0xFF]) {
+		return (Encoding::Utf16Be, 2);
+	}
+
+	let pairs = sample.len() / 2;
+	if pairs > 0 {
+		let even_zeros = sample.iter().step_by(2).filter(|b| **b == 0).count();
+		let odd_zeros = sample.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+		if odd_zeros * 10 > pairs * 3 && even_zeros * 10 < pairs {
+			return (Encoding::Utf16Le, 0);
+		}
+		if even_zeros * 10 > pairs * 3 && odd_zeros * 10 < pairs {
+			return (Encoding::Utf16Be, 0);
+		}
+	}
+	match str::from_utf8(sample) {
+		Ok(_) => (Encoding::Utf8, 0),
+		Err(error) if error.error_len().is_none() => (Encoding::Utf8, 0),
+		Err(_) => (Encoding::Windows1252, 0),
+	}
+}
+
+fn invalid_utf8() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+}
+
+/// Turns the bytes of a text into characters one buffer at a time, keeping
+/// the sequences cut at the end of a buffer for the next one.
+pub struct Decoder {
+	encoding: Encoding,
+	/// When the encoding was guessed, invalid UTF-8 switches the rest of the
+	/// text to windows-1252 instead of failing.
+	fallback: bool,
+	pending: Vec<u8>,
+	surrogate: Option<u16>,
+}
+
+impl Decoder {
+	pub fn new(encoding: Encoding) -> Decoder {
+		Decoder { encoding, fallback: false, pending: Vec::new(), surrogate: None }
+	}
+
+	/// A decoder for a guessed encoding: invalid UTF-8 switches the rest of
+	/// the text to windows-1252 instead of failing.
+	pub fn guessed(encoding: Encoding) -> Decoder {
+		Decoder { fallback: true, ..Decoder::new(encoding) }
+	}
+
+	/// The encoding used so far, which can change from a guessed UTF-8 to
+	/// windows-1252.
+	pub fn encoding(&self) -> Encoding {
+		self.encoding
+	}
+
+	/// Decodes `bytes` and calls `f` with the text, possibly several times.
+	pub fn decode<F: FnMut(&str)>(&mut self, bytes: &[u8], mut f: F) -> io::Result<()> {
+		self.decode_into(bytes, &mut f)
+	}
+
+	fn decode_into(&mut self, bytes: &[u8], f: &mut dyn FnMut(&str)) -> io::Result<()> {
+		match self.encoding {
+			Encoding::Utf8 => self.decode_utf8(bytes, f),
+			Encoding::Utf16Le | Encoding::Utf16Be => {
+				let text = self.decode_utf16(bytes);
+				f(&text);
+				Ok(())
+			}
+			Encoding::Latin1 => {
+				let text: String = bytes.iter().map(|b| *b as char).collect();
+				f(&text);
+				Ok(())
+			}
+			Encoding::Windows1252 => {
+				let text: String = bytes
+					.iter()
+					.map(|b| match b {
+						0x80..=0x9F => WINDOWS_1252[(b - 0x80) as usize],
+						_ => *b as char,
+					})
+					.collect();
+				f(&text);
+				Ok(())
+			}
+		}
+	}
+
+	fn decode_utf8(&mut self, bytes: &[u8], f: &mut dyn FnMut(&str)) -> io::Result<()> {
+		self.pending.extend_from_slice(bytes);
+		let valid = match str::from_utf8(&self.pending) {
+			Ok(text) => text.len(),
+			Err(error) if error.error_len().is_none() => error.valid_up_to(),
+			Err(error) if self.fallback => {
+				let valid = error.valid_up_to();
+				if let Ok(text) = str::from_utf8(&self.pending[..valid]) {
+					f(text);
+				}
+				let rest = self.pending.split_off(valid);
+				self.pending.clear();
+				self.encoding = Encoding::Windows1252;
+				return self.decode_into(&rest, f);
+			}
+			Err(_) => return Err(invalid_utf8()),
+		};
+		if let Ok(text) = str::from_utf8(&self.pending[..valid]) {
+			f(text);
+		}
+		self.pending.drain(..valid);
+		Ok(())
+	}
+
+	fn decode_utf16(&mut self, bytes: &[u8]) -> String {
+		self.pending.extend_from_slice(bytes);
+		let len = self.pending.len() - self.pending.len() % 2;
+		let mut units: Vec<u16> = self.surrogate.take().into_iter().collect();
+		units.extend(self.pending[..len].chunks(2).map(|pair| match self.encoding {
+			Encoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+			_ => u16::from_le_bytes([pair[0], pair[1]]),
+		}));
+		self.pending.drain(..len);
+
+		// A high surrogate at the end waits for its pair in the next buffer.
+		if let Some(last) = units.last() {
+			if (0xD800..0xDC00).contains(last) {
+				self.surrogate = units.pop();
+			}
+		}
+		char::decode_utf16(units).map(|c| c.unwrap_or(REPLACEMENT_CHARACTER)).collect()
+	}
+
+	/// Checks that the text did not end in the middle of a character.
+	pub fn finish<F: FnMut(&str)>(&mut self, mut f: F) -> io::Result<()> {
+		if self.surrogate.take().is_some() {
+			f(&REPLACEMENT_CHARACTER.to_string());
+		}
+		if self.pending.is_empty() {
+			return Ok(());
+		}
+		match self.encoding {
+			Encoding::Utf8 if self.fallback => {
+				let rest = std::mem::take(&mut self.pending);
+				self.encoding = Encoding::Windows1252;
+				self.decode_into(&rest, &mut f)
+			}
+			Encoding::Utf8 => Err(invalid_utf8()),
+			_ => {
+				self.pending.clear();
+				f(&REPLACEMENT_CHARACTER.to_string());
+				Ok(())
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Decodes `bytes` given `chunk` bytes at a time.
+	fn decode(mut decoder: Decoder, bytes: &[u8], chunk: usize) -> io::Result<(String, Encoding)> {
+		let mut text = String::new();
+		for piece in bytes.chunks(chunk) {
+			decoder.decode(piece, |part| text.push_str(part))?;
+		}
+		decoder.finish(|part| text.push_str(part))?;
+		Ok((text, decoder.encoding()))
+	}
+
+	fn utf16le(text: &str) -> Vec<u8> {
+		text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+	}
+
+	fn utf16be(text: &str) -> Vec<u8> {
+		text.encode_utf16().flat_map(u16::to_be_bytes).collect()
+	}
+
+	#[test]
+	fn names() {
+		for encoding in [Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be, Encoding::Latin1, Encoding::Windows1252] {
+			assert_eq!(encoding.to_string().parse(), Ok(encoding));
+		}
+		assert_eq!("UTF_8".parse(), Ok(Encoding::Utf8));
+		assert_eq!("cp1252".parse(), Ok(Encoding::Windows1252));
+		assert!("ebcdic".parse::<Encoding>().is_err());
+	}
+
+	#[test]
+	fn byte_order_marks() {
+		assert_eq!(detect(b"\xEF\xBB\xBFtext"), (Encoding::Utf8, 3));
+		assert_eq!(detect(b"\xFF\xFEt\0"), (Encoding::Utf16Le, 2));
+		assert_eq!(detect(b"\xFE\xFF\0t"), (Encoding::Utf16Be, 2));
+	}
+
+	#[test]
+	fn utf16_without_byte_order_mark() {
+		let text = "Plain ASCII text, with a few accents: été.";
+		assert_eq!(detect(&utf16le(text)), (Encoding::Utf16Le, 0));
+		assert_eq!(detect(&utf16be(text)), (Encoding::Utf16Be, 0));
+
+		// A few zero bytes in a binary-looking text are not enough.
+		assert_eq!(detect(b"abcdefghij\0klmnopqrst"), (Encoding::Utf8, 0));
+	}
+
+	#[test]
+	fn utf8_and_windows_1252() {
+		assert_eq!(detect("déjà vu".as_bytes()), (Encoding::Utf8, 0));
+		// A character cut at the end of the sample is still UTF-8.
+		assert_eq!(detect(&"déjà".as_bytes()[..4]), (Encoding::Utf8, 0));
+		assert_eq!(detect(b"d\xE9j\xE0 vu"), (Encoding::Windows1252, 0));
+		assert_eq!(detect(b""), (Encoding::Utf8, 0));
+	}
+
+	#[test]
+	fn utf8_cut_between_chunks() {
+		let text = "Où est l'été ? 👍🏽 €";
+		for chunk in 1..=5 {
+			let decoded = decode(Decoder::new(Encoding::Utf8), text.as_bytes(), chunk).unwrap();
+			assert_eq!(decoded, (text.to_string(), Encoding::Utf8), "chunks of {} bytes", chunk);
+		}
+	}
+
+	#[test]
+	fn surrogate_pairs_cut_between_chunks() {
+		let text = "a👍b𝄞";
+		for chunk in 1..=5 {
+			let decoded = decode(Decoder::new(Encoding::Utf16Le), &utf16le(text), chunk).unwrap();
+			assert_eq!(decoded.0, text, "little endian, chunks of {} bytes", chunk);
+			let decoded = decode(Decoder::new(Encoding::Utf16Be), &utf16be(text), chunk).unwrap();
+			assert_eq!(decoded.0, text, "big endian, chunks of {} bytes", chunk);
+		}
+	}
+
+	#[test]
+	fn unpaired_surrogates() {
+		// A high surrogate at the end of the text, and a lone low surrogate.
+		let decoded = decode(Decoder::new(Encoding::Utf16Le), &[b'a', 0, 0x3D, 0xD8], 1).unwrap();
+		assert_eq!(decoded.0, "a\u{FFFD}");
+		let decoded = decode(Decoder::new(Encoding::Utf16Le), &[0x00, 0xDC, b'a', 0], 1).unwrap();
+		assert_eq!(decoded.0, "\u{FFFD}a");
+		// An odd byte at the end.
+		let decoded = decode(Decoder::new(Encoding::Utf16Be), &[0, b'a', 0], 2).unwrap();
+		assert_eq!(decoded.0, "a\u{FFFD}");
+	}
+
+	#[test]
+	fn guessed_utf8_falls_back_to_windows_1252() {
+		let mut bytes = "café, ".as_bytes().to_vec();
+		bytes.extend_from_slice(b"caf\xE9 \x80 \x93quoted\x94");
+		for chunk in 1..=4 {
+			let decoded = decode(Decoder::guessed(Encoding::Utf8), &bytes, chunk).unwrap();
+			// The UTF-8 read before the failure stays as it was decoded.
+			assert_eq!(decoded, ("café, café € “quoted”".to_string(), Encoding::Windows1252), "chunks of {} bytes", chunk);
+		}
+
+		// A character cut at the end of the text.
+		let decoded = decode(Decoder::guessed(Encoding::Utf8), b"caf\xC3", 2).unwrap();
+		assert_eq!(decoded, ("cafÃ".to_string(), Encoding::Windows1252));
+	}
+
+	#[test]
+	fn given_utf8_fails() {
+		let error = decode(Decoder::new(Encoding::Utf8), b"caf\xE9 au lait", 3).unwrap_err();
+		assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+		let error = decode(Decoder::new(Encoding::Utf8), b"caf\xC3", 3).unwrap_err();
+		assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn single_byte_encodings() {
+		let bytes = b"\x80 \x8D \xE9";
+		assert_eq!(decode(Decoder::new(Encoding::Latin1), bytes, 1).unwrap().0, "\u{80} \u{8D} é");
+		assert_eq!(decode(Decoder::new(Encoding::Windows1252), bytes, 1).unwrap().0, "€ \u{8D} é");
+	}
+}
@@ -0,0 +1,62 @@
+
+use std::{fmt, io};
+
+/// Why an input could not be analysed. Each kind has its own exit status,
+/// so that scripts can tell them apart.
+#[derive(Debug)]
+pub enum Error {
+	/// The command line could not be understood.
+	Usage(String),
+	NotFound(String),
+	NotAFile(String),
+	PermissionDenied(String),
+	/// The bytes of the input are not valid in its encoding.
+	Decoding(String),
+	/// Nothing to analyse: no path given, or a directory or a pattern
+	/// without any file.
+	EmptyInput(String),
+	/// Any other failure to read an input.
+	Io(String, io::Error),
+}
+
+impl Error {
+	/// The error met reading `name`, from the kind of `error`.
+	pub fn from_io(name: &str, error: io::Error) -> Error {
+		let name = name.to_string();
+		match error.kind() {
+			io::ErrorKind::NotFound => Error::NotFound(name),
+			io::ErrorKind::PermissionDenied => Error::PermissionDenied(name),
+			io::ErrorKind::InvalidData => Error::Decoding(name),
+			_ => Error::Io(name, error),
+		}
+	}
+
+	/// Status the process exits with; 1 is left to `--max-grade`.
+	pub fn exit_code(&self) -> i32 {
+		match self {
+			Error::Usage(_) => 2,
+			Error::NotFound(_) => 3,
+			Error::NotAFile(_) => 4,
+			Error::PermissionDenied(_) => 5,
+			Error::Decoding(_) => 6,
+			Error::EmptyInput(_) => 7,
+			Error::Io(..) => 8,
+		}
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Usage(message) => write!(f, "{}", message),
+			Error::NotFound(name) => write!(f, "{}: given path does not exist", name),
+			Error::NotAFile(name) => write!(f, "{}: only files and directories are accepted", name),
+			Error::PermissionDenied(name) => write!(f, "{}: permission denied", name),
+			Error::Decoding(name) => write!(f, "{}: the text is not valid in its encoding", name),
+			Error::EmptyInput(message) => write!(f, "{}", message),
+			Error::Io(name, error) => write!(f, "{}: failed to read: {}", name, error),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
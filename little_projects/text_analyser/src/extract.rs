@@ -0,0 +1,808 @@
+
+use std::{path::Path, str::FromStr};
+use crate::segmentation::grapheme_count;
+
+/// The kind of document the text comes from, to leave its markup out of the
+/// analysis.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Markup {
+	#[default]
+	Plain,
+	/// Plain text after a YAML (`---`) or TOML (`+++`) front matter block.
+	FrontMatter,
+	Markdown,
+	Html,
+	/// Only the comments and the string literals of Rust source code.
+	Rust,
+}
+
+impl Markup {
+	/// Guesses the markup from the extension of `path`, plain text when it
+	/// is unknown.
+	pub fn from_path(path: &Path) -> Markup {
+		let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+		match extension.as_str() {
+			"md" | "markdown" | "mdown" | "mkd" => Markup::Markdown,
+			"html" | "htm" | "xhtml" => Markup::Html,
+			"rs" => Markup::Rust,
+			_ => Markup::Plain,
+		}
+	}
+}
+
+impl FromStr for Markup {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().as_str() {
+			"plain" | "text" => Ok(Markup::Plain),
+			"front-matter" | "frontmatter" => Ok(Markup::FrontMatter),
+			"markdown" | "md" => Ok(Markup::Markdown),
+			"html" => Ok(Markup::Html),
+			"rust" | "rs" => Ok(Markup::Rust),
+			_ => Err(format!("unknown markup '{}' (expected plain, front-matter, markdown, html or rust)", s)),
+		}
+	}
+}
+
+#[derive(Clone, PartialEq)]
+enum FrontMatter {
+	Start,
+	Inside(&'static str),
+	Done,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Html {
+	Text,
+	Tag,
+	Comment,
+	Entity,
+	RawText,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Rust {
+	Code,
+	/// After a `/` that may start a comment.
+	Slash,
+	LineComment,
+	/// Block comment, with its nesting depth.
+	BlockComment(usize),
+	/// Inside a block comment, after a `*` or a `/`.
+	BlockStar(usize),
+	BlockSlash(usize),
+	Str,
+	StrEscape,
+	/// After `r`, with the number of `#` seen so far.
+	RawStart(usize),
+	RawStr(usize),
+	/// After the `"` that may close a raw string, with the `#` seen so far.
+	RawEnd(usize, usize),
+	/// After a `'`: a character literal or a lifetime.
+	Quote,
+	/// After `'` and one character, a lifetime unless `'` follows.
+	QuoteChar,
+	/// After the `\` of a character literal.
+	CharEscape,
+	/// After the escaped character of a character literal, until its
+	/// closing `'`, as in `'\u{e9}'`.
+	CharEnd,
+}
+
+const BLOCK_TAGS: &[&str] = &[
+	"p", "div", "h1", "h2", "h3", "h4", "h5", "h6", "li", "tr", "title", "section", "article", "header",
+	"footer", "blockquote", "pre", "table", "ul", "ol", "dd", "dt", "main", "nav", "aside", "figcaption",
+	"caption", "hr",
+];
+
+fn html_entity(name: &str) -> Option<char> {
+	if let Some(number) = name.strip_prefix('#') {
+		let code = match number.strip_prefix(['x', 'X']) {
+			Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+			None => number.parse().ok()?,
+		};
+		return char::from_u32(code);
+	}
+	let c = match name {
+		"amp" => '&',
+		"lt" => '<',
+		"gt" => '>',
+		"quot" => '"',
+		"apos" => '\'',
+		"nbsp" => ' ',
+		"mdash" => '—',
+		"ndash" => '–',
+		"hellip" => '…',
+		"lsquo" => '‘',
+		"rsquo" => '’',
+		"ldquo" => '“',
+		"rdquo" => '”',
+		"laquo" => '«',
+		"raquo" => '»',
+		"copy" => '©',
+		"eacute" => 'é',
+		"egrave" => 'è',
+		"agrave" => 'à',
+		"ccedil" => 'ç',
+		_ => return None,
+	};
+	Some(c)
+}
+
+/// Index of the first `close` after `from` that is not nested in another
+/// `open`.
+fn find_closing(chars: &[char], from: usize, open: char, close: char) -> Option<usize> {
+	let mut depth = 0;
+	for (i, c) in chars.iter().enumerate().skip(from) {
+		if *c == open {
+			depth += 1;
+		} else if *c == close {
+			if depth == 0 {
+				return Some(i);
+			}
+			depth -= 1;
+		}
+	}
+	None
+}
+
+/// Removes the inline Markdown syntax of a line: code spans, link and image
+/// targets, HTML tags, emphasis markers and table pipes.
+fn markdown_inline(line: &str, out: &mut String) {
+	let chars: Vec<char> = line.chars().collect();
+	let mut i = 0;
+
+	while i < chars.len() {
+		let c = chars[i];
+		match c {
+			'\\' if i + 1 < chars.len() => {
+				out.push(chars[i + 1]);
+				i += 2;
+				continue;
+			}
+			'`' => {
+				let run = chars[i..].iter().take_while(|c| **c == '`').count();
+				let mut end = None;
+				let mut j = i + run;
+				while j < chars.len() {
+					let other = chars[j..].iter().take_while(|c| **c == '`').count();
+					if other == run {
+						end = Some(j + run);
+						break;
+					}
+					j += other.max(1);
+				}
+				i = end.unwrap_or(i + run);
+				continue;
+			}
+			'!' if chars.get(i + 1) == Some(&'[') => {
+				i += 1;
+				continue;
+			}
+			'[' => {
+				if let Some(close) = find_closing(&chars, i + 1, '[', ']') {
+					let inner: String = chars[i + 1..close].iter().collect();
+					let after = close + 1;
+					if chars.get(after) == Some(&':') && i == 0 {
+						// A reference definition: "[id]: url".
+						return;
+					}
+					markdown_inline(&inner, out);
+					i = match chars.get(after) {
+						Some('(') => find_closing(&chars, after + 1, '(', ')').map(|end| end + 1).unwrap_or(after),
+						Some('[') => find_closing(&chars, after + 1, '[', ']').map(|end| end + 1).unwrap_or(after),
+						_ => after,
+					};
+					continue;
+				}
+			}
+			'<' if chars.get(i + 1).is_some_and(|next| next.is_alphabetic() || *next == '/' || *next == '!') => {
+				if let Some(close) = chars[i..].iter().position(|c| *c == '>') {
+					i += close + 1;
+					continue;
+				}
+			}
+			'*' | '~' => {
+				i += 1;
+				continue;
+			}
+			'_' => {
+				let before = i > 0 && chars[i - 1].is_alphanumeric();
+				let after = chars.get(i + 1).is_some_and(|c| c.is_alphanumeric());
+				if !(before && after) {
+					i += 1;
+					continue;
+				}
+			}
+			'|' => {
+				out.push(' ');
+				i += 1;
+				continue;
+			}
+			_ => (),
+		}
+		out.push(c);
+		i += 1;
+	}
+}
+
+/// Strips a list marker ("-", "*", "+", "1.", "1)") and a task box.
+fn strip_list_marker(line: &str) -> Option<&str> {
+	let rest = if let Some(rest) = line.strip_prefix(['-', '*', '+']) {
+		rest
+	} else {
+		let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+		if digits == 0 || digits > 9 {
+			return None;
+		}
+		line[digits..].strip_prefix(['.', ')'])?
+	};
+	if !rest.starts_with([' ', '\t']) {
+		return None;
+	}
+	let rest = rest.trim_start();
+	Some(
+		rest.strip_prefix("[ ] ")
+			.or_else(|| rest.strip_prefix("[x] "))
+			.or_else(|| rest.strip_prefix("[X] "))
+			.unwrap_or(rest),
+	)
+}
+
+/// Turns a document into the plain text to analyse, one piece at a time.
+/// Line based markups keep the end of a cut line for the next piece; the
+/// others carry their state from one character to the next.
+#[derive(Clone)]
+pub struct Extractor {
+	markup: Markup,
+	line: String,
+	front_matter: FrontMatter,
+	fence: Option<String>,
+	previous_blank: bool,
+	in_code: bool,
+	in_list: bool,
+	html: Html,
+	buffer: String,
+	raw_end: String,
+	rust: Rust,
+	previous: char,
+}
+
+impl Extractor {
+	pub fn new(markup: Markup) -> Extractor {
+		Extractor {
+			markup,
+			line: String::new(),
+			front_matter: FrontMatter::Start,
+			fence: None,
+			previous_blank: true,
+			in_code: false,
+			in_list: false,
+			html: Html::Text,
+			buffer: String::new(),
+			raw_end: String::new(),
+			rust: Rust::Code,
+			previous: ' ',
+		}
+	}
+
+	pub fn markup(&self) -> Markup {
+		self.markup
+	}
+
+	/// Appends to `out` the text extracted from the next piece of the
+	/// document.
+	pub fn push(&mut self, text: &str, out: &mut String) {
+		match self.markup {
+			Markup::Plain => out.push_str(text),
+			Markup::FrontMatter | Markup::Markdown => {
+				for piece in text.split_inclusive('\n') {
+					self.line.push_str(piece);
+					if self.line.ends_with('\n') {
+						let line = std::mem::take(&mut self.line);
+						self.push_line(&line, out);
+					}
+				}
+			}
+			Markup::Html => text.chars().for_each(|c| self.html_char(c, out)),
+			Markup::Rust => text.chars().for_each(|c| self.rust_char(c, out)),
+		}
+	}
+
+	/// Extracts what is left at the end of the document.
+	pub fn finish(&mut self, out: &mut String) {
+		if !self.line.is_empty() {
+			let line = std::mem::take(&mut self.line);
+			self.push_line(&line, out);
+		}
+		if self.markup == Markup::Html && self.html == Html::Entity {
+			out.push('&');
+			out.push_str(&self.buffer);
+		}
+	}
+
+	fn push_line(&mut self, line: &str, out: &mut String) {
+		let trimmed = line.trim();
+		match self.front_matter {
+			FrontMatter::Start => {
+				self.front_matter = FrontMatter::Done;
+				let closing = match trimmed {
+					"---" => Some("---"),
+					"+++" => Some("+++"),
+					_ => None,
+				};
+				if let Some(closing) = closing {
+					self.front_matter = FrontMatter::Inside(closing);
+					return;
+				}
+			}
+			FrontMatter::Inside(closing) => {
+				if trimmed == closing || (closing == "---" && trimmed == "...") {
+					self.front_matter = FrontMatter::Done;
+				}
+				return;
+			}
+			FrontMatter::Done => (),
+		}
+		if self.markup == Markup::Markdown {
+			self.markdown_line(line, out);
+		} else {
+			out.push_str(line);
+		}
+	}
+
+	fn markdown_line(&mut self, line: &str, out: &mut String) {
+		let trimmed = line.trim();
+		let indent = line.chars().take_while(|c| *c == ' ' || *c == '\t').map(|c| if c == '\t' { 4 } else { 1 }).sum::<usize>();
+
+		if let Some(fence) = &self.fence {
+			if trimmed.starts_with(fence.as_str()) {
+				self.fence = None;
+			}
+			out.push('\n');
+			return;
+		}
+		if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+			self.fence = Some(trimmed[..3].to_string());
+			out.push('\n');
+			return;
+		}
+		if trimmed.is_empty() {
+			self.previous_blank = true;
+			out.push('\n');
+			return;
+		}
+		if indent >= 4 && (self.in_code || (self.previous_blank && !self.in_list)) {
+			self.in_code = true;
+			out.push('\n');
+			return;
+		}
+		if self.previous_blank && indent == 0 {
+			self.in_list = false;
+		}
+		self.in_code = false;
+		self.previous_blank = false;
+
+		if trimmed.len() >= 3 && trimmed.chars().all(|c| "-=*_ ".contains(c)) {
+			// A thematic break or the underline of a heading.
+			out.push_str("\n\n");
+			return;
+		}
+		if trimmed.contains('|') && trimmed.chars().all(|c| "|-: ".contains(c)) {
+			return;
+		}
+
+		let mut text = trimmed;
+		while let Some(rest) = text.strip_prefix('>') {
+			text = rest.trim_start();
+		}
+		if text.starts_with('#') {
+			let heading = text.trim_start_matches('#').trim_end_matches('#').trim();
+			markdown_inline(heading, out);
+			out.push_str("\n\n");
+			return;
+		}
+		if let Some(item) = strip_list_marker(text) {
+			self.in_list = true;
+			// Each item is its own sentence.
+			out.push('\n');
+			text = item;
+		}
+		markdown_inline(text, out);
+		out.push('\n');
+	}
+
+	fn html_char(&mut self, c: char, out: &mut String) {
+		match self.html {
+			Html::Text => match c {
+				'<' => {
+					self.html = Html::Tag;
+					self.buffer.clear();
+				}
+				'&' => {
+					self.html = Html::Entity;
+					self.buffer.clear();
+				}
+				_ => out.push(c),
+			},
+			Html::Tag => {
+				if self.buffer.is_empty() && !(c.is_alphabetic() || c == '/' || c == '!' || c == '?') {
+					out.push('<');
+					self.html = Html::Text;
+					self.html_char(c, out);
+				} else if c == '>' {
+					self.html = Html::Text;
+					let tag = std::mem::take(&mut self.buffer);
+					self.html_tag(&tag, out);
+				} else {
+					self.buffer.push(c);
+					if self.buffer == "!--" {
+						self.html = Html::Comment;
+						self.buffer.clear();
+					}
+				}
+			}
+			Html::Comment => {
+				self.buffer.push(c);
+				if self.buffer.ends_with("-->") {
+					self.html = Html::Text;
+					self.buffer.clear();
+				} else if self.buffer.len() > 3 {
+					self.buffer.remove(0);
+				}
+			}
+			Html::Entity => {
+				if c == ';' {
+					self.html = Html::Text;
+					match html_entity(&self.buffer) {
+						Some(decoded) => out.push(decoded),
+						None => {
+							out.push('&');
+							out.push_str(&self.buffer);
+							out.push(';');
+						}
+					}
+				} else if (c.is_alphanumeric() || c == '#') && self.buffer.len() < 10 {
+					self.buffer.push(c);
+				} else {
+					self.html = Html::Text;
+					out.push('&');
+					out.push_str(&self.buffer);
+					self.html_char(c, out);
+				}
+			}
+			Html::RawText => {
+				self.buffer.push(c.to_ascii_lowercase());
+				if self.buffer.ends_with(self.raw_end.as_str()) {
+					self.html = Html::Tag;
+					self.buffer = self.raw_end[1..].to_string();
+				} else if self.buffer.len() > self.raw_end.len() {
+					let first = self.buffer.chars().next().map(char::len_utf8).unwrap_or(0);
+					self.buffer.drain(..first);
+				}
+			}
+		}
+	}
+
+	fn html_tag(&mut self, tag: &str, out: &mut String) {
+		let closing = tag.starts_with('/');
+		let name: String = tag
+			.trim_start_matches('/')
+			.chars()
+			.take_while(|c| c.is_alphanumeric())
+			.collect::<String>()
+			.to_ascii_lowercase();
+
+		if !closing && (name == "script" || name == "style") && !tag.ends_with('/') {
+			self.html = Html::RawText;
+			self.raw_end = format!("</{}", name);
+			self.buffer.clear();
+		} else if name == "br" {
+			out.push('\n');
+		} else if name == "td" || name == "th" {
+			out.push(' ');
+		} else if BLOCK_TAGS.contains(&name.as_str()) {
+			out.push_str("\n\n");
+		}
+	}
+
+	fn rust_char(&mut self, c: char, out: &mut String) {
+		let previous = self.previous;
+		self.previous = c;
+		match self.rust {
+			Rust::Code => match c {
+				'/' => self.rust = Rust::Slash,
+				'"' => self.rust = Rust::Str,
+				'\'' => self.rust = Rust::Quote,
+				'r' if !(previous.is_alphanumeric() || previous == '_') || previous == 'b' => {
+					self.rust = Rust::RawStart(0)
+				}
+				_ => (),
+			},
+			Rust::Slash => match c {
+				'/' => self.rust = Rust::LineComment,
+				'*' => self.rust = Rust::BlockComment(1),
+				_ => {
+					self.rust = Rust::Code;
+					self.rust_char(c, out);
+				}
+			},
+			Rust::LineComment => {
+				if c == '\n' {
+					self.rust = Rust::Code;
+					out.push('\n');
+				} else if !((c == '/' || c == '!') && (previous == '/' || previous == '!') && self.buffer.is_empty()) {
+					// The "///" and "//!" of doc comments are left out.
+					self.buffer.push(c);
+					out.push(c);
+				}
+				if c == '\n' {
+					self.buffer.clear();
+				}
+			}
+			Rust::BlockComment(depth) => match c {
+				'*' => self.rust = Rust::BlockStar(depth),
+				'/' => self.rust = Rust::BlockSlash(depth),
+				_ => out.push(c),
+			},
+			Rust::BlockStar(depth) => match c {
+				'/' if depth == 1 => {
+					self.rust = Rust::Code;
+					out.push('\n');
+				}
+				'/' => self.rust = Rust::BlockComment(depth - 1),
+				'*' => (),
+				_ => {
+					self.rust = Rust::BlockComment(depth);
+					self.rust_char(c, out);
+				}
+			},
+			Rust::BlockSlash(depth) => match c {
+				'*' => self.rust = Rust::BlockComment(depth + 1),
+				_ => {
+					self.rust = Rust::BlockComment(depth);
+					self.rust_char(c, out);
+				}
+			},
+			Rust::Str => match c {
+				'"' => {
+					self.rust = Rust::Code;
+					out.push('\n');
+				}
+				'\\' => self.rust = Rust::StrEscape,
+				_ => out.push(c),
+			},
+			Rust::StrEscape => {
+				self.rust = Rust::Str;
+				match c {
+					'n' => out.push('\n'),
+					't' | 'r' | '\n' => out.push(' '),
+					'0' | 'x' | 'u' => (),
+					_ => out.push(c),
+				}
+			}
+			Rust::RawStart(hashes) => match c {
+				'#' => self.rust = Rust::RawStart(hashes + 1),
+				'"' => self.rust = Rust::RawStr(hashes),
+				_ => {
+					self.rust = Rust::Code;
+					self.rust_char(c, out);
+				}
+			},
+			Rust::RawStr(hashes) => match c {
+				'"' if hashes == 0 => {
+					self.rust = Rust::Code;
+					out.push('\n');
+				}
+				'"' => self.rust = Rust::RawEnd(hashes, 0),
+				_ => out.push(c),
+			},
+			Rust::RawEnd(hashes, seen) => match c {
+				'#' if seen + 1 == hashes => {
+					self.rust = Rust::Code;
+					out.push('\n');
+				}
+				'#' => self.rust = Rust::RawEnd(hashes, seen + 1),
+				_ => {
+					out.push('"');
+					out.push_str(&"#".repeat(seen));
+					self.rust = Rust::RawStr(hashes);
+					self.rust_char(c, out);
+				}
+			},
+			Rust::Quote => match c {
+				'\\' => self.rust = Rust::CharEscape,
+				_ => self.rust = Rust::QuoteChar,
+			},
+			Rust::QuoteChar => {
+				self.rust = Rust::Code;
+				if c != '\'' {
+					// A lifetime: the character is code.
+					self.rust_char(c, out);
+				}
+			}
+			Rust::CharEscape => self.rust = Rust::CharEnd,
+			Rust::CharEnd => {
+				if c == '\'' {
+					self.rust = Rust::Code;
+				}
+			}
+		}
+	}
+}
+
+/// Extracts the text of a document line by line, keeping every line as read
+/// to report positions in the document.
+#[derive(Clone)]
+pub struct LineExtractor {
+	extractor: Option<Extractor>,
+	line: String,
+	number: usize,
+	extracted: String,
+}
+
+impl LineExtractor {
+	pub fn new(markup: Markup) -> LineExtractor {
+		let extractor = match markup {
+			Markup::Plain => None,
+			markup => Some(Extractor::new(markup)),
+		};
+		LineExtractor { extractor, line: String::new(), number: 0, extracted: String::new() }
+	}
+
+	/// Feeds the next piece of the document, a line can be cut between
+	/// pieces. `f` is called with the number, the text as read and the
+	/// extracted text of every line ended.
+	pub fn push<F: FnMut(usize, &str, &str)>(&mut self, text: &str, mut f: F) {
+		for piece in text.split_inclusive('\n') {
+			self.line.push_str(piece);
+			if self.line.ends_with('\n') {
+				self.end_line(false, &mut f);
+			}
+		}
+	}
+
+	/// Ends the last line, even without a line break.
+	pub fn finish<F: FnMut(usize, &str, &str)>(&mut self, mut f: F) {
+		if !self.line.is_empty() || self.extractor.is_some() {
+			self.end_line(true, &mut f);
+		}
+	}
+
+	fn end_line(&mut self, last: bool, f: &mut dyn FnMut(usize, &str, &str)) {
+		let mut extracted = std::mem::take(&mut self.extracted);
+		match self.extractor.as_mut() {
+			Some(extractor) => {
+				extractor.push(&self.line, &mut extracted);
+				if last {
+					extractor.finish(&mut extracted);
+				}
+			}
+			None => extracted.push_str(&self.line),
+		}
+		self.number += 1;
+		f(self.number, &self.line, &extracted);
+		self.line.clear();
+		extracted.clear();
+		self.extracted = extracted;
+	}
+}
+
+/// Finds the extracted pieces of a line back in the line as read, in order.
+pub struct Columns<'a> {
+	line: &'a str,
+	/// Byte offset after the last piece found.
+	from: usize,
+}
+
+impl<'a> Columns<'a> {
+	pub fn new(line: &'a str) -> Columns<'a> {
+		Columns { line, from: 0 }
+	}
+
+	/// Column of `piece` in user-perceived characters, from 1, searched after
+	/// the previous piece. A piece changed by the markup, like an HTML
+	/// entity, is given the column after the previous one.
+	pub fn find(&mut self, piece: &str) -> usize {
+		let at = match self.line[self.from..].find(piece) {
+			Some(at) => {
+				let start = self.from + at;
+				self.from = start + piece.len();
+				start
+			}
+			None => self.from,
+		};
+		grapheme_count(&self.line[..at]) + 1
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// The words extracted from `text`, given two characters at a time, with
+	/// single spaces between them.
+	fn extract(markup: Markup, text: &str) -> String {
+		let mut extractor = Extractor::new(markup);
+		let mut out = String::new();
+		let chars: Vec<char> = text.chars().collect();
+		for piece in chars.chunks(2) {
+			extractor.push(&piece.iter().collect::<String>(), &mut out);
+		}
+		extractor.finish(&mut out);
+		out.split_whitespace().collect::<Vec<&str>>().join(" ")
+	}
+
+	#[test]
+	fn markup_from_path() {
+		assert!(Markup::from_path(Path::new("notes.md")) == Markup::Markdown);
+		assert!(Markup::from_path(Path::new("index.HTML")) == Markup::Html);
+		assert!(Markup::from_path(Path::new("main.rs")) == Markup::Rust);
+		assert!(Markup::from_path(Path::new("notes.txt")) == Markup::Plain);
+	}
+
+	#[test]
+	fn front_matter() {
+		let text = "---\ntitle: Hello\ntags: [a, b]\n---\nThe text.\n";
+		assert_eq!(extract(Markup::FrontMatter, text), "The text.");
+		assert_eq!(extract(Markup::FrontMatter, "+++\ntitle = 'x'\n+++\nBody"), "Body");
+		assert_eq!(extract(Markup::FrontMatter, "No header\n---\nhere"), "No header --- here");
+	}
+
+	#[test]
+	fn markdown_blocks() {
+		let text = "# A *title* #\n\nSome text.\n\n```rust\nlet code = 1;\n```\n\n    indented code\n\n> quoted\n> text\n\n- one\n- two\n\n---\n| a | b |\n|---|---|\n";
+		assert_eq!(extract(Markup::Markdown, text), "A title Some text. quoted text one two a b");
+	}
+
+	#[test]
+	fn markdown_inline() {
+		let text = "A **bold** and _em_ [link](http://x.y) with `code` and ![alt text](img.png).\n";
+		assert_eq!(extract(Markup::Markdown, text), "A bold and em link with and alt text.");
+	}
+
+	#[test]
+	fn html() {
+		let text = "<html><head><style>p { x: y }</style><script>let a = '<p>';</script></head>\
+			<body><h1>Title</h1><p>Caf&eacute; &amp; th&#233; &lt;3 <!-- hidden --></p></body></html>";
+		assert_eq!(extract(Markup::Html, text), "Title Café & thé <3");
+	}
+
+	#[test]
+	fn rust_comments_and_strings() {
+		let text = "//! Crate docs.\n/// Item docs.\nfn f<'a>(x: &'a str) -> char {\n\t/* block /* nested */ end */\n\tprintln!(\"a \\\"quoted\\\" word\\n\");\n\tlet raw = r#\"raw \"text\"\"#;\n\tif x == \"'\" { '\\'' } else { 'b' }\n}\n// After.\n";
+		assert_eq!(
+			extract(Markup::Rust, text),
+			"Crate docs. Item docs. block nested end a \"quoted\" word raw \"text\" ' After."
+		);
+	}
+
+	#[test]
+	fn rust_escaped_quote_literal() {
+		let text = "let q = ['\\'','\"']; let s = \"after\"; let u = '\\u{e9}'; // done\n";
+		assert_eq!(extract(Markup::Rust, text), "after done");
+	}
+
+	#[test]
+	fn lines_and_columns() {
+		let mut lines = LineExtractor::new(Markup::Markdown);
+		let mut found = Vec::new();
+		let mut collect = |number: usize, line: &str, extracted: &str| {
+			found.push((number, line.to_string(), extracted.trim().to_string()));
+		};
+		lines.push("# Ti", &mut collect);
+		lines.push("tle\n\nSome *text*", &mut collect);
+		lines.finish(&mut collect);
+		assert_eq!(found, vec![
+			(1, "# Title\n".to_string(), "Title".to_string()),
+			(2, "\n".to_string(), String::new()),
+			(3, "Some *text*".to_string(), "Some text".to_string()),
+		]);
+
+		let mut columns = Columns::new("Some *text* é text");
+		assert_eq!(columns.find("Some"), 1);
+		assert_eq!(columns.find("text"), 7);
+		assert_eq!(columns.find("text"), 15);
+	}
+}
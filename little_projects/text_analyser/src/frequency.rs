@@ -0,0 +1,191 @@
+
+use std::{cmp::Ordering, collections::HashMap, str::FromStr};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use crate::{stemmer::Reduction, stopwords::StopWords};
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Normalization {
+	#[default]
+	None,
+	Nfc,
+	Nfkc,
+}
+
+impl FromStr for Normalization {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().as_str() {
+			"none" => Ok(Normalization::None),
+			"nfc" => Ok(Normalization::Nfc),
+			"nfkc" => Ok(Normalization::Nfkc),
+			_ => Err(format!("unknown normalization '{}' (expected none, nfc or nfkc)", s)),
+		}
+	}
+}
+
+/// How the words of the text are turned into the words counted by the
+/// frequency table. Everything is off by default, so "The" and "the" are
+/// two different words.
+#[derive(Clone, Default)]
+pub struct WordOptions {
+	pub case_fold: bool,
+	pub strip_punctuation: bool,
+	pub normalization: Normalization,
+	pub stop_words: StopWords,
+	/// Grouping of the inflected forms, applied after the other options.
+	pub reduction: Reduction,
+}
+
+impl WordOptions {
+	/// Returns the word to count for `token`, or `None` when nothing is left
+	/// of it once its punctuation is stripped or when it is a stop word.
+	/// Words are already free of punctuation around them: stripping leaves
+	/// out the apostrophes and periods inside them, as in "don't" or "e.g".
+	pub fn normalise(&self, token: &str) -> Option<String> {
+		if self.stop_words.contains(token) {
+			return None;
+		}
+		let mut word: String = match self.normalization {
+			Normalization::None => token.to_string(),
+			Normalization::Nfc => token.nfc().collect(),
+			Normalization::Nfkc => token.nfkc().collect(),
+		};
+		if self.case_fold {
+			word = word.to_lowercase();
+		}
+		if self.strip_punctuation {
+			word.retain(|c| c.is_alphanumeric() || is_combining_mark(c));
+		}
+		if word.is_empty() {
+			None
+		} else {
+			Some(word)
+		}
+	}
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Order {
+	#[default]
+	Descending,
+	Ascending,
+}
+
+pub struct Frequency {
+	pub word: String,
+	pub count: usize,
+	/// Share of all the counted words, between 0 and 100.
+	pub percent: f64,
+}
+
+/// Number of occurrences of every distinct word.
+#[derive(Clone, Default)]
+pub struct FrequencyTable {
+	counts: HashMap<String, usize>,
+	total: usize,
+}
+
+impl FrequencyTable {
+	pub fn new() -> FrequencyTable {
+		FrequencyTable::default()
+	}
+
+	pub fn add(&mut self, word: String) {
+		self.add_count(word, 1);
+	}
+
+	pub fn add_count(&mut self, word: String, count: usize) {
+		*self.counts.entry(word).or_insert(0) += count;
+		self.total += count;
+	}
+
+	pub fn merge(&mut self, other: &FrequencyTable) {
+		for (word, count) in other.iter() {
+			self.add_count(word.clone(), count);
+		}
+	}
+
+	pub fn count(&self, word: &str) -> usize {
+		self.counts.get(word).copied().unwrap_or(0)
+	}
+
+	/// Number of words counted, repetitions included.
+	pub fn total(&self) -> usize {
+		self.total
+	}
+
+	/// Number of distinct words.
+	pub fn distinct(&self) -> usize {
+		self.counts.len()
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (&String, usize)> {
+		self.counts.iter().map(|(word, count)| (word, *count))
+	}
+
+	/// The words sharing the highest count, sorted, and that count.
+	pub fn most_used(&self) -> (Vec<String>, usize) {
+		let max = self.counts.values().copied().max().unwrap_or(0);
+		let mut words: Vec<String> = self.counts
+			.iter()
+			.filter(|(_, count)| **count == max)
+			.map(|(word, _)| word.clone())
+			.collect();
+		words.sort();
+		(words, max)
+	}
+
+	/// The `limit` most (or least, with [`Order::Ascending`]) frequent
+	/// words, or all of them without a limit. Ties are sorted
+	/// alphabetically.
+	pub fn top(&self, limit: Option<usize>, order: Order) -> Vec<Frequency> {
+		let mut entries: Vec<(&String, usize)> = self.iter().collect();
+		entries.sort_by(|a, b| {
+			let by_count = match order {
+				Order::Descending => b.1.cmp(&a.1),
+				Order::Ascending => a.1.cmp(&b.1),
+			};
+			match by_count {
+				Ordering::Equal => a.0.cmp(b.0),
+				other => other,
+			}
+		});
+		entries
+			.into_iter()
+			.take(limit.unwrap_or(usize::MAX))
+			.map(|(word, count)| Frequency {
+				word: word.clone(),
+				count,
+				percent: if self.total == 0 { 0.0 } else { count as f64 * 100.0 / self.total as f64 },
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn strip_punctuation() {
+		let options = WordOptions { strip_punctuation: true, ..WordOptions::default() };
+		assert_eq!(options.normalise("don't").as_deref(), Some("dont"));
+		assert_eq!(options.normalise("e.g").as_deref(), Some("eg"));
+		assert_eq!(options.normalise("l’été").as_deref(), Some("lété"));
+		// Accents written with a combining character are kept.
+		assert_eq!(options.normalise("e\u{301}te\u{301}").as_deref(), Some("e\u{301}te\u{301}"));
+		assert_eq!(options.normalise("'").as_deref(), None);
+		assert_eq!(WordOptions::default().normalise("don't").as_deref(), Some("don't"));
+	}
+
+	#[test]
+	fn stripped_words_are_counted_together() {
+		let options = WordOptions { case_fold: true, strip_punctuation: true, ..WordOptions::default() };
+		let mut table = FrequencyTable::new();
+		for word in "Don't go. I dont know, e.g. the E.G. way.".split_whitespace() {
+			table.add(options.normalise(word).unwrap());
+		}
+		assert_eq!((table.count("dont"), table.count("eg"), table.distinct()), (2, 2, 7));
+	}
+}
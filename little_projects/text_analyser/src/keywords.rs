@@ -0,0 +1,110 @@
+
+use std::{
+	cmp::Ordering,
+	collections::HashMap,
+	fs::{read_to_string, File},
+	io::{self, BufWriter, Write},
+	path::Path,
+};
+use crate::frequency::FrequencyTable;
+
+/// A word and how characteristic it is of a document.
+pub struct Keyword {
+	pub word: String,
+	pub count: usize,
+	/// Frequency of the word in the document times its inverse document
+	/// frequency.
+	pub score: f64,
+}
+
+/// In how many documents of a corpus every word appears.
+#[derive(Clone, Default)]
+pub struct Idf {
+	documents: usize,
+	counts: HashMap<String, usize>,
+}
+
+fn invalid(line: usize) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, format!("invalid IDF table at line {}", line))
+}
+
+impl Idf {
+	pub fn new() -> Idf {
+		Idf::default()
+	}
+
+	/// Counts the words of one more document.
+	pub fn add_document(&mut self, table: &FrequencyTable) {
+		self.documents += 1;
+		for (word, _) in table.iter() {
+			*self.counts.entry(word.clone()).or_insert(0) += 1;
+		}
+	}
+
+	/// Adds the documents counted by `other`.
+	pub fn merge(&mut self, other: &Idf) {
+		self.documents += other.documents;
+		for (word, count) in &other.counts {
+			*self.counts.entry(word.clone()).or_insert(0) += count;
+		}
+	}
+
+	pub fn documents(&self) -> usize {
+		self.documents
+	}
+
+	/// Inverse document frequency of `word`, smoothed so that a word of
+	/// every document still weighs 1 and an unknown word the most.
+	pub fn idf(&self, word: &str) -> f64 {
+		let count = self.counts.get(word).copied().unwrap_or(0);
+		((1 + self.documents) as f64 / (1 + count) as f64).ln() + 1.0
+	}
+
+	/// The `limit` words of `table` with the highest TF-IDF, or all of them
+	/// without a limit. Ties are sorted by word.
+	pub fn keywords(&self, table: &FrequencyTable, limit: Option<usize>) -> Vec<Keyword> {
+		let total = table.total().max(1) as f64;
+		let mut result: Vec<Keyword> = table
+			.iter()
+			.map(|(word, count)| Keyword { word: word.clone(), count, score: count as f64 / total * self.idf(word) })
+			.collect();
+		result.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal).then_with(|| a.word.cmp(&b.word)));
+		result.truncate(limit.unwrap_or(usize::MAX));
+		result
+	}
+
+	/// Writes the table to `path`: the number of documents on the first
+	/// line, then each word and its document count separated by a tab.
+	pub fn save(&self, path: &Path) -> io::Result<()> {
+		let mut words: Vec<(&String, &usize)> = self.counts.iter().collect();
+		words.sort();
+		let mut out = BufWriter::new(File::create(path)?);
+		writeln!(out, "# IDF table written by text_analyser")?;
+		writeln!(out, "documents\t{}", self.documents)?;
+		for (word, count) in words {
+			writeln!(out, "{}\t{}", word, count)?;
+		}
+		out.flush()
+	}
+
+	/// Reads a table written by [`Idf::save`].
+	pub fn load(path: &Path) -> io::Result<Idf> {
+		let content = read_to_string(path)?;
+		let mut idf = Idf::new();
+		let mut lines = content.lines().enumerate().filter(|(_, line)| !line.starts_with('#') && !line.trim().is_empty());
+
+		match lines.next() {
+			Some((i, line)) if line.starts_with("documents\t") => {
+				idf.documents = line["documents\t".len()..].trim().parse().map_err(|_| invalid(i + 1))?;
+			}
+			Some((i, _)) => return Err(invalid(i + 1)),
+			None => return Ok(idf),
+		}
+		for (i, line) in lines {
+			let (word, count) = line.rsplit_once('\t').ok_or(invalid(i + 1))?;
+			let count = count.trim().parse().map_err(|_| invalid(i + 1))?;
+			idf.counts.insert(word.to_string(), count);
+		}
+		Ok(idf)
+	}
+}
@@ -0,0 +1,52 @@
+
+use std::{fmt, str::FromStr};
+
+/// The languages the analyser has word lists and rules for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+	#[default]
+	English,
+	French,
+	Spanish,
+	German,
+}
+
+impl Language {
+	pub const ALL: [Language; 4] = [Language::English, Language::French, Language::Spanish, Language::German];
+
+	/// The ISO 639-1 code of the language.
+	pub fn code(&self) -> &'static str {
+		match self {
+			Language::English => "en",
+			Language::French => "fr",
+			Language::Spanish => "es",
+			Language::German => "de",
+		}
+	}
+}
+
+impl fmt::Display for Language {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			Language::English => "english",
+			Language::French => "french",
+			Language::Spanish => "spanish",
+			Language::German => "german",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+impl FromStr for Language {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().as_str() {
+			"en" | "english" => Ok(Language::English),
+			"fr" | "french" => Ok(Language::French),
+			"es" | "spanish" => Ok(Language::Spanish),
+			"de" | "german" => Ok(Language::German),
+			_ => Err(format!("unknown language '{}' (expected en, fr, es or de)", s)),
+		}
+	}
+}
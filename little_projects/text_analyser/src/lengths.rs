@@ -0,0 +1,160 @@
+
+/// Distribution of lengths (of words, of sentences...), kept as the number
+/// of occurrences of every length so it takes no more memory for a large
+/// text than for a small one.
+#[derive(Clone, Default)]
+pub struct Distribution {
+	counts: Vec<usize>,
+	total: usize,
+	sum: usize,
+}
+
+/// Lengths from `start` to `end`, both included, and how many there are.
+pub struct Bucket {
+	pub start: usize,
+	pub end: usize,
+	pub count: usize,
+}
+
+impl Distribution {
+	pub fn new() -> Distribution {
+		Distribution::default()
+	}
+
+	pub fn add(&mut self, length: usize) {
+		if self.counts.len() <= length {
+			self.counts.resize(length + 1, 0);
+		}
+		self.counts[length] += 1;
+		self.total += 1;
+		self.sum += length;
+	}
+
+	pub fn merge(&mut self, other: &Distribution) {
+		if self.counts.len() < other.counts.len() {
+			self.counts.resize(other.counts.len(), 0);
+		}
+		for (length, count) in other.counts.iter().enumerate() {
+			self.counts[length] += count;
+		}
+		self.total += other.total;
+		self.sum += other.sum;
+	}
+
+	/// Number of lengths added.
+	pub fn count(&self) -> usize {
+		self.total
+	}
+
+	pub fn sum(&self) -> usize {
+		self.sum
+	}
+
+	pub fn mean(&self) -> f64 {
+		if self.total == 0 {
+			0.0
+		} else {
+			self.sum as f64 / self.total as f64
+		}
+	}
+
+	pub fn min(&self) -> usize {
+		self.counts.iter().position(|count| *count > 0).unwrap_or(0)
+	}
+
+	pub fn max(&self) -> usize {
+		self.counts.iter().rposition(|count| *count > 0).unwrap_or(0)
+	}
+
+	/// The length at `rank` (from 0) once all of them are sorted.
+	fn nth(&self, rank: usize) -> usize {
+		let mut seen = 0;
+		for (length, count) in self.counts.iter().enumerate() {
+			seen += count;
+			if seen > rank {
+				return length;
+			}
+		}
+		0
+	}
+
+	pub fn median(&self) -> f64 {
+		if self.total == 0 {
+			return 0.0;
+		}
+		let middle = self.total / 2;
+		if self.total % 2 == 1 {
+			self.nth(middle) as f64
+		} else {
+			(self.nth(middle - 1) + self.nth(middle)) as f64 / 2.0
+		}
+	}
+
+	/// The smallest length greater than or equal to `percent` percent of the
+	/// lengths (nearest-rank method).
+	pub fn percentile(&self, percent: f64) -> usize {
+		if self.total == 0 {
+			return 0;
+		}
+		let rank = (percent.clamp(0.0, 100.0) / 100.0 * self.total as f64).ceil() as usize;
+		self.nth(rank.max(1) - 1)
+	}
+
+	/// Number of occurrences of every length from 0 to the longest one.
+	pub fn counts(&self) -> &[usize] {
+		&self.counts
+	}
+
+	/// Groups the lengths from the shortest to the longest one in at most
+	/// `buckets` buckets of the same width.
+	pub fn histogram(&self, buckets: usize) -> Vec<Bucket> {
+		if self.total == 0 {
+			return Vec::new();
+		}
+		let (min, max) = (self.min(), self.max());
+		let width = (max - min) / buckets.max(1) + 1;
+
+		let mut result = Vec::new();
+		let mut start = min;
+		while start <= max {
+			let end = (start + width - 1).min(max);
+			let count = self.counts[start..=end].iter().sum();
+			result.push(Bucket { start, end, count });
+			start = end + 1;
+		}
+		result
+	}
+}
+
+/// Draws the buckets as horizontal bars, the longest one being `width`
+/// characters long.
+pub fn bar_chart(buckets: &[Bucket], width: usize) -> String {
+	let labels: Vec<String> = buckets
+		.iter()
+		.map(|bucket| {
+			if bucket.start == bucket.end {
+				bucket.start.to_string()
+			} else {
+				format!("{}-{}", bucket.start, bucket.end)
+			}
+		})
+		.collect();
+	let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+	let highest = buckets.iter().map(|bucket| bucket.count).max().unwrap_or(0).max(1);
+
+	let mut chart = String::new();
+	for (bucket, label) in buckets.iter().zip(labels) {
+		let mut len = bucket.count * width / highest;
+		if len == 0 && bucket.count > 0 {
+			len = 1;
+		}
+		chart.push_str(&format!(
+			"{:>label_width$} | {}{} {}\n",
+			label,
+			"#".repeat(len),
+			" ".repeat(width - len),
+			bucket.count
+		));
+	}
+	chart
+}
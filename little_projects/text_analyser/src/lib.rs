@@ -0,0 +1,600 @@
+
+pub mod analyser;
+pub mod compare;
+pub mod config;
+pub mod corpus;
+pub mod detection;
+pub mod diagnostic;
+pub mod encoding;
+pub mod error;
+pub mod extract;
+pub mod frequency;
+pub mod keywords;
+pub mod language;
+pub mod lengths;
+pub mod lines;
+pub mod ngrams;
+pub mod readability;
+pub mod report;
+pub mod segmentation;
+pub mod sentences;
+pub mod spelling;
+pub mod stemmer;
+pub mod stopwords;
+pub mod style;
+
+use std::{io::{self, BufRead}, sync::Arc};
+use analyser::{Counters, Metric, MetricValue, Token};
+use detection::Detection;
+use diagnostic::Diagnostic;
+use encoding::{Decoder, Encoding};
+use extract::{Extractor, Markup};
+use frequency::{FrequencyTable, WordOptions};
+use language::Language;
+use lengths::Distribution;
+use lines::LineStats;
+use ngrams::NgramCounter;
+use readability::Readability;
+use sentences::SentenceSegmenter;
+use spelling::{Dictionary, Speller, Spelling};
+use stemmer::Reduction;
+use style::{Style, StyleChecker, StyleRules};
+
+/// Everything that can be tuned in the analysis.
+#[derive(Clone)]
+pub struct Options {
+	/// Language of the text, for the syllable rules and the abbreviations.
+	pub language: Language,
+	/// Replace `language` with the one detected from the start of the text.
+	pub detect_language: bool,
+	/// Leave out the stop words of the detected language.
+	pub auto_stop_words: bool,
+	pub words: WordOptions,
+	/// Sizes of the n-grams to count, none by default.
+	pub ngrams: Vec<usize>,
+	/// Least number of occurrences for an n-gram to be reported.
+	pub ngram_min_count: usize,
+	/// Markup to leave out of the text, none by default.
+	pub markup: Markup,
+	/// Measure the lines of the text as read, for [`Stats::line_stats`].
+	pub line_stats: bool,
+	/// Width above which lines are reported, none by default.
+	pub max_line_width: Option<usize>,
+	/// Metrics computed on top of the built-in ones.
+	pub metrics: Vec<Box<dyn Metric>>,
+	/// Words known to the spelling check, which is skipped without them.
+	pub dictionary: Option<Arc<Dictionary>>,
+	/// Style rules the text is checked with, none to skip the check.
+	pub style: Option<StyleRules>,
+}
+
+impl Default for Options {
+	fn default() -> Options {
+		Options {
+			language: Language::default(),
+			detect_language: false,
+			auto_stop_words: false,
+			words: WordOptions::default(),
+			ngrams: Vec::new(),
+			ngram_min_count: 2,
+			markup: Markup::Plain,
+			line_stats: false,
+			max_line_width: None,
+			metrics: Vec::new(),
+			dictionary: None,
+			style: None,
+		}
+	}
+}
+
+/// Statistics of a text, filled by [`Stats::analyse`].
+#[derive(Clone)]
+pub struct Stats {
+	phrases: usize,
+	words: usize,
+	chars: usize,
+	code_points: usize,
+	bytes: usize,
+	lines: usize,
+	phrases_len: Distribution,
+	word_len: Distribution,
+	use_words: Vec<String>,
+	use_count: usize,
+	frequencies: FrequencyTable,
+	ngrams: Vec<NgramCounter>,
+	syllables: usize,
+	polysyllables: usize,
+	readability: Option<Readability>,
+	encoding: Option<Encoding>,
+	language: Option<Detection>,
+	line_stats: Option<LineStats>,
+	metrics: Vec<MetricValue>,
+	spelling: Option<Spelling>,
+	style: Option<Style>,
+}
+
+/// Running state of the single pass over the text. The text can be fed in
+/// as many pieces as needed: a word or a line cut between two pieces is
+/// counted as if the text was given at once.
+#[derive(Default)]
+pub struct StatsBuilder {
+	counters: Counters,
+	lines: usize,
+	word_len: Distribution,
+	token: String,
+	line_started: bool,
+	blank_line: bool,
+	sentences: SentenceSegmenter,
+	options: Options,
+	frequencies: FrequencyTable,
+	ngrams: Vec<NgramCounter>,
+	encoding: Option<Encoding>,
+	extractor: Option<Extractor>,
+	extracted: String,
+	/// Start of the text, to detect its language from.
+	sample: String,
+	detected: bool,
+	language: Option<Detection>,
+	/// Lines of the text before its markup is removed.
+	line_stats: Option<LineStats>,
+	speller: Option<Speller>,
+	style: Option<StyleChecker>,
+	/// Text the style check waits for the detected language with.
+	style_sample: String,
+}
+
+/// Length in bytes of the start of a text its language is detected from.
+const SAMPLE_LEN: usize = 4096;
+/// Longest text as read the style check waits for the detected language
+/// with, markup included.
+const STYLE_SAMPLE_LEN: usize = 16 * SAMPLE_LEN;
+/// Least number of bytes the encoding of a text is detected from, unless
+/// it is shorter, enough for any byte order mark.
+const DETECT_LEN: usize = 4;
+
+impl StatsBuilder {
+	pub fn new() -> StatsBuilder {
+		StatsBuilder::default()
+	}
+
+	pub fn with_options(options: Options) -> StatsBuilder {
+		let ngrams = options.ngrams.iter().map(|n| NgramCounter::new(*n)).collect();
+		let sentences = SentenceSegmenter::new(options.language);
+		let counters = Counters::new(options.language);
+		let extractor = match options.markup {
+			Markup::Plain => None,
+			markup => Some(Extractor::new(markup)),
+		};
+		let line_stats = options.line_stats.then(|| LineStats::new(options.max_line_width));
+		let speller = options.dictionary.clone().map(|dictionary| Speller::new(dictionary, options.markup));
+		let style = options.style.clone().map(|rules| StyleChecker::new(rules, options.language, options.markup));
+		StatsBuilder {
+			options,
+			ngrams,
+			sentences,
+			counters,
+			extractor,
+			line_stats,
+			speller,
+			style,
+			..StatsBuilder::default()
+		}
+	}
+
+	/// Feeds the next piece of the text, after removing its markup.
+	pub fn feed(&mut self, text: &str) {
+		if let Some(line_stats) = self.line_stats.as_mut() {
+			line_stats.push(text);
+		}
+		if let Some(speller) = self.speller.as_mut() {
+			speller.push(text);
+		}
+		if let Some(style) = self.style.as_mut() {
+			if self.options.detect_language && !self.detected {
+				self.style_sample.push_str(text);
+			} else {
+				style.push(text);
+			}
+		}
+		match self.extractor.as_mut() {
+			Some(extractor) => {
+				let mut extracted = std::mem::take(&mut self.extracted);
+				extractor.push(text, &mut extracted);
+				self.feed_plain(&extracted);
+				extracted.clear();
+				self.extracted = extracted;
+			}
+			None => self.feed_plain(text),
+		}
+		// Markup can make most of the text: the style check does not wait
+		// for a full sample past this length.
+		if !self.detected && self.style_sample.len() >= STYLE_SAMPLE_LEN {
+			self.detect_language();
+		}
+	}
+
+	fn feed_plain(&mut self, mut text: &str) {
+		if !self.detected {
+			let mut cut = text.len().min(SAMPLE_LEN - self.sample.len());
+			while !text.is_char_boundary(cut) {
+				cut -= 1;
+			}
+			self.sample.push_str(&text[..cut]);
+			if cut < text.len() || self.sample.len() >= SAMPLE_LEN {
+				self.detect_language();
+			}
+			// The language rules apply from the first word: the sample waits
+			// for the detection, then the rest of the text follows it.
+			if self.options.detect_language {
+				if !self.detected {
+					return;
+				}
+				text = &text[cut..];
+			}
+		}
+		for c in text.chars() {
+			self.push(c);
+		}
+	}
+
+	fn detect_language(&mut self) {
+		self.detected = true;
+		let sample = std::mem::take(&mut self.sample);
+		self.language = detection::detect(&sample);
+		if !self.options.detect_language {
+			return;
+		}
+		if let Some(detection) = self.language {
+			self.options.language = detection.language;
+			self.sentences = SentenceSegmenter::new(detection.language);
+			self.counters = Counters::new(detection.language);
+			if self.options.auto_stop_words {
+				self.options.words.stop_words.add_builtin(detection.language);
+			}
+			if let Some(rules) = self.options.style.clone() {
+				self.style = Some(StyleChecker::new(rules, detection.language, self.options.markup));
+			}
+		}
+		if let Some(style) = self.style.as_mut() {
+			style.push(&std::mem::take(&mut self.style_sample));
+		}
+		for c in sample.chars() {
+			self.push(c);
+		}
+	}
+
+	/// Feeds everything `reader` yields, one buffer at a time, without
+	/// keeping the whole text in memory. The bytes are decoded from
+	/// `encoding`, or from the encoding detected from the first ones when
+	/// `None`; a byte order mark is skipped. Returns the encoding used.
+	pub fn read<R: BufRead>(&mut self, mut reader: R, encoding: Option<Encoding>) -> io::Result<Encoding> {
+		// A pipe can give fewer bytes than a byte order mark at first.
+		let mut head = Vec::new();
+		while head.len() < DETECT_LEN {
+			let chunk = reader.fill_buf()?;
+			if chunk.is_empty() {
+				break;
+			}
+			head.extend_from_slice(chunk);
+			let len = chunk.len();
+			reader.consume(len);
+		}
+		let (detected, bom) = encoding::detect(&head);
+		let (mut decoder, bom) = match encoding {
+			Some(encoding) => (Decoder::new(encoding), if encoding == detected { bom } else { 0 }),
+			None => (Decoder::guessed(detected), bom),
+		};
+		decoder.decode(&head[bom..], |text| self.feed(text))?;
+
+		loop {
+			let chunk = reader.fill_buf()?;
+			if chunk.is_empty() {
+				break;
+			}
+			let len = chunk.len();
+			decoder.decode(chunk, |text| self.feed(text))?;
+			reader.consume(len);
+		}
+		decoder.finish(|text| self.feed(text))?;
+		self.encoding = Some(decoder.encoding());
+		Ok(decoder.encoding())
+	}
+
+	fn end_token(&mut self) {
+		if self.token.is_empty() {
+			return;
+		}
+		let token = std::mem::take(&mut self.token);
+		let words: Vec<&str> = segmentation::words(&token).collect();
+		let new_sentence = self.sentences.push(&token, words.len());
+		if new_sentence || words.is_empty() {
+			self.ngrams.iter_mut().for_each(NgramCounter::reset);
+		}
+
+		let piece = Token { text: &token, words: &words, sentence_start: new_sentence };
+		self.counters.update(&piece);
+		for metric in self.options.metrics.iter_mut() {
+			metric.update(&piece);
+		}
+		for word in words {
+			self.add_word(word);
+		}
+		self.token = token;
+		self.token.clear();
+	}
+
+	fn add_word(&mut self, word: &str) {
+		self.word_len.add(segmentation::grapheme_count(word));
+
+		// N-grams are made of consecutive words of a same sentence: filtered
+		// words break the sequence.
+		let mut word = self.options.words.normalise(word);
+		if self.options.words.reduction != Reduction::None {
+			word = word.map(|word| stemmer::reduce(&word, self.options.words.reduction, self.options.language));
+		}
+		for counter in self.ngrams.iter_mut() {
+			match &word {
+				Some(word) => counter.push(word),
+				None => counter.reset(),
+			}
+		}
+		if let Some(word) = word {
+			self.frequencies.add(word);
+		}
+	}
+
+	fn push(&mut self, c: char) {
+		if c == '\n' {
+			if self.line_started {
+				self.lines += 1;
+			}
+			self.line_started = false;
+		} else {
+			self.line_started = true;
+		}
+
+		if c.is_whitespace() {
+			self.end_token();
+		} else {
+			self.token.push(c);
+			self.blank_line = false;
+		}
+
+		if c == '\n' {
+			if self.blank_line {
+				self.sentences.paragraph_break();
+			}
+			self.blank_line = true;
+		}
+	}
+
+	pub fn finish(mut self) -> Stats {
+		if let Some(mut extractor) = self.extractor.take() {
+			let mut extracted = String::new();
+			extractor.finish(&mut extracted);
+			self.feed_plain(&extracted);
+		}
+		if !self.detected {
+			self.detect_language();
+		}
+		if let Some(line_stats) = self.line_stats.as_mut() {
+			line_stats.finish();
+		}
+		self.end_token();
+		if self.line_started {
+			self.lines += 1;
+		}
+		let sentences = self.sentences.finish();
+		let (use_words, use_count) = self.frequencies.most_used();
+		let counters = &self.counters;
+		let words = counters.words.finalize() as usize;
+		let syllables = counters.syllables.finalize() as usize;
+		let polysyllables = counters.syllables.polysyllables();
+
+		Stats {
+			phrases: sentences.count(),
+			words,
+			chars: counters.characters.finalize() as usize,
+			code_points: counters.code_points.finalize() as usize,
+			bytes: counters.bytes.finalize() as usize,
+			lines: self.lines,
+			readability: Readability::compute(words, sentences.count(), syllables, polysyllables),
+			phrases_len: sentences,
+			word_len: self.word_len,
+			use_words,
+			use_count,
+			frequencies: self.frequencies,
+			ngrams: self.ngrams,
+			syllables,
+			polysyllables,
+			encoding: self.encoding,
+			language: self.language,
+			line_stats: self.line_stats,
+			metrics: self.options.metrics.iter().map(|metric| MetricValue::of(metric.as_ref())).collect(),
+			spelling: self.speller.map(Speller::finish),
+			style: self.style.map(StyleChecker::finish),
+		}
+	}
+}
+
+impl Stats {
+	/// Computes every statistic of `text` in a single pass.
+	pub fn analyse(text: &str) -> Stats {
+		let mut builder = StatsBuilder::new();
+		builder.feed(text);
+		builder.finish()
+	}
+
+	/// Computes the statistics of everything `reader` yields, without
+	/// keeping the whole text in memory.
+	pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Stats> {
+		let mut builder = StatsBuilder::new();
+		builder.read(reader, None)?;
+		Ok(builder.finish())
+	}
+
+	/// Adds the figures of `other` to these ones, as if both texts were one.
+	/// Both must have been analysed with the same [`Options`].
+	pub fn merge(&mut self, other: &Stats) {
+		self.phrases += other.phrases;
+		self.words += other.words;
+		self.chars += other.chars;
+		self.code_points += other.code_points;
+		self.bytes += other.bytes;
+		self.lines += other.lines;
+		self.phrases_len.merge(&other.phrases_len);
+		self.word_len.merge(&other.word_len);
+		self.frequencies.merge(&other.frequencies);
+		for (counter, other) in self.ngrams.iter_mut().zip(&other.ngrams) {
+			counter.merge(other);
+		}
+		self.syllables += other.syllables;
+		self.polysyllables += other.polysyllables;
+		if self.encoding != other.encoding {
+			self.encoding = None;
+		}
+		if let (Some(line_stats), Some(other)) = (self.line_stats.as_mut(), &other.line_stats) {
+			line_stats.merge(other);
+		}
+		for (metric, other) in self.metrics.iter_mut().zip(&other.metrics) {
+			metric.merge(other);
+		}
+		if let (Some(spelling), Some(other)) = (self.spelling.as_mut(), &other.spelling) {
+			spelling.merge(other);
+		}
+		if let (Some(style), Some(other)) = (self.style.as_mut(), &other.style) {
+			style.merge(other);
+		}
+		self.language = match (self.language, other.language) {
+			(Some(mine), Some(theirs)) if mine.language == theirs.language => {
+				Some(Detection { language: mine.language, confidence: mine.confidence.min(theirs.confidence) })
+			}
+			_ => None,
+		};
+
+		(self.use_words, self.use_count) = self.frequencies.most_used();
+		self.readability = Readability::compute(self.words, self.phrases, self.syllables, self.polysyllables);
+	}
+
+	/// The figures of all the `stats` together, `None` without any.
+	pub fn total<'a, I: IntoIterator<Item = &'a Stats>>(stats: I) -> Option<Stats> {
+		let mut stats = stats.into_iter();
+		let mut total = stats.next()?.clone();
+		for other in stats {
+			total.merge(other);
+		}
+		Some(total)
+	}
+
+	/// Number of non-whitespace characters, counted as grapheme clusters.
+	pub fn chars(&self) -> usize {
+		self.chars
+	}
+	/// Number of Unicode code points of the non-whitespace characters.
+	pub fn code_points(&self) -> usize {
+		self.code_points
+	}
+	/// Size in UTF-8 bytes of the non-whitespace characters.
+	pub fn bytes(&self) -> usize {
+		self.bytes
+	}
+	/// Number of words, as found by [`segmentation::words`].
+	pub fn words(&self) -> usize {
+		self.words
+	}
+	/// Number of sentences, as found by [`SentenceSegmenter`].
+	pub fn phrases(&self) -> usize {
+		self.phrases
+	}
+	/// Number of non-empty lines.
+	pub fn lines(&self) -> usize {
+		self.lines
+	}
+	/// Average length of a word, in grapheme clusters.
+	pub fn word_len(&self) -> f64 {
+		self.word_len.mean()
+	}
+	/// Average number of words per sentence.
+	pub fn phrases_len(&self) -> f64 {
+		self.phrases_len.mean()
+	}
+	/// Number of words of the longest sentence.
+	pub fn max_phrase_len(&self) -> usize {
+		self.phrases_len.max()
+	}
+	/// Length of every word, in grapheme clusters.
+	pub fn word_lengths(&self) -> &Distribution {
+		&self.word_len
+	}
+	/// Length of every sentence, in words.
+	pub fn phrase_lengths(&self) -> &Distribution {
+		&self.phrases_len
+	}
+	/// The most used words, sorted, or an empty slice for an empty text.
+	pub fn most_used_words(&self) -> &[String] {
+		&self.use_words
+	}
+	/// How many times each of the most used words appears.
+	pub fn most_used_count(&self) -> usize {
+		self.use_count
+	}
+	/// Occurrences of every word, normalised as asked to the builder.
+	pub fn frequencies(&self) -> &FrequencyTable {
+		&self.frequencies
+	}
+	/// One counter per n-gram size asked in the [`Options`].
+	pub fn ngrams(&self) -> &[NgramCounter] {
+		&self.ngrams
+	}
+	/// Estimated number of syllables of all the words.
+	pub fn syllables(&self) -> usize {
+		self.syllables
+	}
+	/// Encoding the text was read from, `None` when it was given as a
+	/// string or for a total of texts in different encodings.
+	pub fn encoding(&self) -> Option<Encoding> {
+		self.encoding
+	}
+	/// Language detected from the start of the text, `None` when nothing
+	/// looks like a known language or for a total of texts in different
+	/// languages.
+	pub fn language(&self) -> Option<Detection> {
+		self.language
+	}
+	/// Line by line statistics of the text as read, markup included,
+	/// `None` without [`Options::line_stats`].
+	pub fn line_stats(&self) -> Option<&LineStats> {
+		self.line_stats.as_ref()
+	}
+	/// Values of the metrics added to [`Options::metrics`], in order.
+	pub fn metrics(&self) -> &[MetricValue] {
+		&self.metrics
+	}
+	/// Unknown words of the text, `None` without a [`Options::dictionary`].
+	pub fn spelling(&self) -> Option<&Spelling> {
+		self.spelling.as_ref()
+	}
+	/// Problems of style found in the text, `None` without
+	/// [`Options::style`].
+	pub fn style(&self) -> Option<&Style> {
+		self.style.as_ref()
+	}
+	/// Every problem found in the text, by line and column: the ones of
+	/// [`LineStats::diagnostics`], the unknown words and the problems of
+	/// style.
+	pub fn diagnostics(&self) -> Vec<Diagnostic> {
+		let mut diagnostics = self.line_stats.as_ref().map(LineStats::diagnostics).unwrap_or_default();
+		if let Some(spelling) = &self.spelling {
+			diagnostics.extend(spelling.diagnostics());
+		}
+		if let Some(style) = &self.style {
+			diagnostics.extend(style.diagnostics().iter().cloned());
+		}
+		diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+		diagnostics
+	}
+	/// Readability indices, `None` for a text without words or phrases.
+	pub fn readability(&self) -> Option<Readability> {
+		self.readability
+	}
+}
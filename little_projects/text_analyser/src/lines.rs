@@ -0,0 +1,235 @@
+
+use std::fmt;
+use crate::{diagnostic::Diagnostic, segmentation::grapheme_count};
+
+/// Number of longest lines kept with their line number.
+const LONGEST: usize = 5;
+
+/// A line and its width in user-perceived characters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Line {
+	/// Line number, from 1.
+	pub number: usize,
+	pub width: usize,
+}
+
+/// How the lines of a text end.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineEndings {
+	/// A single line without a line break.
+	None,
+	Lf,
+	Crlf,
+	/// Both kinds of line breaks.
+	Mixed,
+}
+
+impl fmt::Display for LineEndings {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = match self {
+			LineEndings::None => "none",
+			LineEndings::Lf => "lf",
+			LineEndings::Crlf => "crlf",
+			LineEndings::Mixed => "mixed",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+/// Line by line statistics of the raw text, line numbers included: what a
+/// linter of prose and configuration files looks at.
+#[derive(Clone, Default)]
+pub struct LineStats {
+	/// Lines longer than this are reported, none without a limit.
+	max_width: Option<usize>,
+	total: usize,
+	blank: usize,
+	blank_run: usize,
+	/// Longest run of blank lines, with its first line.
+	longest_blank_run: Option<Line>,
+	longest: Vec<Line>,
+	too_long: Vec<Line>,
+	/// Lines ending with whitespace, with the column it starts at.
+	trailing_whitespace: Vec<Line>,
+	tab_indented: usize,
+	space_indented: usize,
+	mixed_indentation: Vec<usize>,
+	lf: usize,
+	crlf: usize,
+	/// First line ending with a line break of the kind not used first.
+	other_ending: Option<usize>,
+	line: String,
+}
+
+impl LineStats {
+	pub fn new(max_width: Option<usize>) -> LineStats {
+		LineStats { max_width, ..LineStats::default() }
+	}
+
+	/// Feeds the next piece of the text; a line can be cut between pieces.
+	pub fn push(&mut self, text: &str) {
+		for piece in text.split_inclusive('\n') {
+			self.line.push_str(piece);
+			if self.line.ends_with('\n') {
+				self.end_line();
+			}
+		}
+	}
+
+	/// Counts the last line when the text does not end with a line break.
+	pub fn finish(&mut self) {
+		if !self.line.is_empty() {
+			self.end_line();
+		}
+	}
+
+	fn end_line(&mut self) {
+		let mut line = std::mem::take(&mut self.line);
+		self.total += 1;
+		let number = self.total;
+		if line.ends_with("\r\n") {
+			self.crlf += 1;
+			line.truncate(line.len() - 2);
+		} else if line.ends_with('\n') {
+			self.lf += 1;
+			line.truncate(line.len() - 1);
+		}
+		if self.other_ending.is_none() && self.lf > 0 && self.crlf > 0 {
+			self.other_ending = Some(number);
+		}
+
+		let content = line.trim_end();
+		if content.is_empty() {
+			self.blank += 1;
+			self.blank_run += 1;
+			if self.longest_blank_run.is_none_or(|run| self.blank_run > run.width) {
+				self.longest_blank_run = Some(Line { number: number + 1 - self.blank_run, width: self.blank_run });
+			}
+		} else {
+			self.blank_run = 0;
+			if content.len() < line.len() {
+				self.trailing_whitespace.push(Line { number, width: grapheme_count(content) + 1 });
+			}
+		}
+
+		let indent: String = line.chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+		if !content.is_empty() && !indent.is_empty() {
+			match (indent.contains('\t'), indent.contains(' ')) {
+				(true, true) => self.mixed_indentation.push(number),
+				(true, false) => self.tab_indented += 1,
+				_ => self.space_indented += 1,
+			}
+		}
+
+		let width = grapheme_count(&line);
+		let current = Line { number, width };
+		if self.max_width.is_some_and(|max| width > max) {
+			self.too_long.push(current);
+		}
+		if self.longest.len() < LONGEST || self.longest.last().is_some_and(|last| width > last.width) {
+			let at = self.longest.iter().position(|other| width > other.width).unwrap_or(self.longest.len());
+			self.longest.insert(at, current);
+			self.longest.truncate(LONGEST);
+		}
+		self.line = line;
+		self.line.clear();
+	}
+
+	/// Adds the counts of `other`. Line numbers belong to a single text:
+	/// the lists of lines are cleared.
+	pub fn merge(&mut self, other: &LineStats) {
+		self.total += other.total;
+		self.blank += other.blank;
+		self.tab_indented += other.tab_indented;
+		self.space_indented += other.space_indented;
+		self.lf += other.lf;
+		self.crlf += other.crlf;
+		self.longest_blank_run = None;
+		self.other_ending = None;
+		self.longest.clear();
+		self.too_long.clear();
+		self.trailing_whitespace.clear();
+		self.mixed_indentation.clear();
+	}
+
+	pub fn max_width(&self) -> Option<usize> {
+		self.max_width
+	}
+	/// Number of lines, blank ones included.
+	pub fn total(&self) -> usize {
+		self.total
+	}
+	/// Number of empty or whitespace-only lines.
+	pub fn blank(&self) -> usize {
+		self.blank
+	}
+	/// Longest run of consecutive blank lines: its first line and its
+	/// length in `width`.
+	pub fn longest_blank_run(&self) -> Option<Line> {
+		self.longest_blank_run
+	}
+	/// The longest lines, the longest first.
+	pub fn longest(&self) -> &[Line] {
+		&self.longest
+	}
+	/// Lines wider than the limit, in order.
+	pub fn too_long(&self) -> &[Line] {
+		&self.too_long
+	}
+	/// Lines ending with whitespace, `width` being the column of the first
+	/// trailing whitespace.
+	pub fn trailing_whitespace(&self) -> &[Line] {
+		&self.trailing_whitespace
+	}
+	/// Numbers of the lines indented with both tabs and spaces.
+	pub fn mixed_indentation(&self) -> &[usize] {
+		&self.mixed_indentation
+	}
+	pub fn tab_indented(&self) -> usize {
+		self.tab_indented
+	}
+	pub fn space_indented(&self) -> usize {
+		self.space_indented
+	}
+	/// Number of lines ending with LF and with CRLF.
+	pub fn line_breaks(&self) -> (usize, usize) {
+		(self.lf, self.crlf)
+	}
+	pub fn endings(&self) -> LineEndings {
+		match (self.lf, self.crlf) {
+			(0, 0) => LineEndings::None,
+			(_, 0) => LineEndings::Lf,
+			(0, _) => LineEndings::Crlf,
+			_ => LineEndings::Mixed,
+		}
+	}
+
+	/// The problems a linter reports, by line and column: lines too long,
+	/// trailing whitespace, indentation mixing tabs and spaces and line
+	/// breaks of both kinds.
+	pub fn diagnostics(&self) -> Vec<Diagnostic> {
+		let mut diagnostics = Vec::new();
+		if let Some(max) = self.max_width {
+			for line in &self.too_long {
+				diagnostics.push(Diagnostic::new(
+					line.number,
+					max + 1,
+					"line-too-long",
+					format!("line is {} characters long, above {}", line.width, max),
+				));
+			}
+		}
+		for line in &self.trailing_whitespace {
+			diagnostics.push(Diagnostic::new(line.number, line.width, "trailing-whitespace", "trailing whitespace".to_string()));
+		}
+		for number in &self.mixed_indentation {
+			diagnostics.push(Diagnostic::new(*number, 1, "mixed-indentation", "indentation mixes tabs and spaces".to_string()));
+		}
+		if let Some(number) = self.other_ending {
+			let message = format!("line breaks mix LF ({} lines) and CRLF ({} lines)", self.lf, self.crlf);
+			diagnostics.push(Diagnostic::new(number, 1, "mixed-line-endings", message));
+		}
+		diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+		diagnostics
+	}
+}
@@ -1,142 +1,687 @@
 
-use std::{collections::HashMap, env, fs::*, path::Path, result};
-
-#[allow(dead_code)]
-pub struct Stats {
-	phrases: u64,
-	words: u64,
-	chars: u128,
-	phrases_len: u32,
-	word_len: u32,
-	use_words: String,
-}
-
-#[allow(dead_code)]
-impl Stats {
-	fn count_chars(text: &String) -> usize {
-		text
-		.chars()
-		.filter(|c| c.is_whitespace() == false)
-		.count()
-	}
-	fn count_lines(text: &String) -> usize {
-		text
-		.split(|c| c == '\n')
-		.filter(|str| str.is_empty() == false)
-		.count()
-	}
-	fn count_words(text: &String) -> usize
-	{
-		text
-		.split_whitespace()
-		.filter(|str| str.contains(|c: char| c.is_alphabetic()))
-		.count()
-	}
-	fn count_phrases(text: &String) -> usize
-	{
-		text
-		.split(|c| c == '.' || c == '?' || c == '!')
-		.filter(|phrase| phrase.trim().is_empty() == false)
-		.count()
-	}
-	fn word_len(text: &String) -> usize
-	{
-		let word_count = Self::count_words(text);
-		let mut len: usize = 0;
-		let _ = text
-		.split_whitespace()
-		.filter(|str| str.contains(|c: char| c.is_alphabetic()))
-		.map(|word| len += word.len());
-		return len / word_count;
-	}
-	fn most_used_word(text: &String) -> Option<Vec<String>>
-	{
-		let mut word_count = HashMap::new();
-		let words: Vec<String> = text
-			.split_whitespace()
-			.map(|tmp| tmp.to_string())
-			.collect();
-	
-		if words.len() == 0 {
-			return None;
-		} for word in words {
-			word_count
-			.entry(word)
-			.and_modify(|count: &mut usize| *count += 1)
-			.or_insert(1);
-		}
-		let mut result = Vec::new();
-		let max = *word_count.values().max().unwrap();
-		for word in word_count.iter() {
-			if *word.1 == max {
-				result.push(word.0.clone());
-			}
-		}
-		if result.is_empty() == true {
-			return None;
-		} else {
-			return Some(result);
-		}
-	}
-}
+mod cli;
+mod watch;
+
+use std::{env, fs::File, io::{stdin, BufReader}, path::Path, process};
+use cli::{Args, Input, USAGE};
+use text_analyser::{
+	compare::{self, Comparison},
+	corpus,
+	error::Error,
+	extract::Markup,
+	frequency::Frequency,
+	keywords::{Idf, Keyword},
+	lengths::{self, Distribution},
+	lines::{Line, LineEndings, LineStats},
+	ngrams::{Ngram, NgramCounter},
+	readability::Readability,
+	report::{self, Format, Value},
+	segmentation::grapheme_count,
+	spelling::Spelling,
+	style::Style,
+	Stats, StatsBuilder,
+};
 
-fn is_valid_path(path: &Path) -> bool {
-	if path.exists() == false {
-		println!("Given path does not exists");
-		return false;
-	} else if path.is_file() == false {
-		println!("Only files are accepted");
-		return false;
+fn check_path(path: &Path) -> Result<(), Error> {
+	let name = path.display().to_string();
+	if !path.exists() {
+		return Err(Error::NotFound(name));
+	} else if !path.is_file() {
+		return Err(Error::NotAFile(name));
 	}
-	true
+	Ok(())
 }
 
-fn main() {
+fn prompt_path() -> Result<String, Error> {
 	let mut input: String = String::new();
-	let path;
 	println!("enter a file path:");
-	match std::io::stdin().read_line(&mut input) {
-		Ok(_) => {
-			path = Path::new(input.trim());	
-			if is_valid_path(path) == false {
-				return ;
-			}
-		} Err(_) => {
-			println!("bad input");
-			return ;
+	match stdin().read_line(&mut input) {
+		Ok(_) if input.trim().is_empty() => Err(Error::EmptyInput("No path given".to_string())),
+		Ok(_) => Ok(input.trim().to_string()),
+		Err(error) => Err(Error::from_io("<stdin>", error)),
+	}
+}
+
+fn analyse_input(input: &Input, args: &Args) -> Result<Stats, Error> {
+	let mut options = args.options.clone();
+	options.markup = args.markup.unwrap_or(match input {
+		Input::Stdin => Markup::Plain,
+		Input::File(path) => Markup::from_path(path),
+	});
+	let mut builder = StatsBuilder::with_options(options);
+	let result = match input {
+		Input::Stdin => builder.read(stdin().lock(), args.encoding),
+		Input::File(path) => File::open(path).and_then(|file| builder.read(BufReader::new(file), args.encoding)),
+	};
+	match result {
+		Ok(_) => Ok(builder.finish()),
+		Err(error) => Err(Error::from_io(&input.name(), error)),
+	}
+}
+
+/// Pads `text` to `width` user-perceived characters.
+fn pad(text: &str, width: usize) -> String {
+	let len = grapheme_count(text);
+	format!("{}{}", text, " ".repeat(width.saturating_sub(len)))
+}
+
+fn print_frequencies(stats: &Stats, args: &Args) {
+	let table = stats.frequencies().top(args.top, args.order);
+	if table.is_empty() {
+		return;
+	}
+	let width = table.iter().map(|entry| grapheme_count(&entry.word)).max().unwrap_or(0).max(4);
+
+	println!();
+	println!("Word frequencies ({} of {} distinct words):", table.len(), stats.frequencies().distinct());
+	println!("{:>5}  {:<width$}  {:>7}  {:>7}", "rank", "word", "count", "percent");
+	for (i, entry) in table.iter().enumerate() {
+		println!("{:>5}  {}  {:>7}  {:>6.2}%", i + 1, pad(&entry.word, width), entry.count, entry.percent);
+	}
+}
+
+fn print_keywords(stats: &Stats, idf: &Idf, limit: usize) {
+	let table = idf.keywords(stats.frequencies(), if limit == 0 { None } else { Some(limit) });
+	if table.is_empty() {
+		return;
+	}
+	let width = table.iter().map(|entry| grapheme_count(&entry.word)).max().unwrap_or(0).max(4);
+
+	println!();
+	println!("Keywords (TF-IDF over {} documents):", idf.documents());
+	println!("{:>5}  {:<width$}  {:>7}  {:>7}", "rank", "word", "count", "score");
+	for (i, entry) in table.iter().enumerate() {
+		println!("{:>5}  {}  {:>7}  {:>7.4}", i + 1, pad(&entry.word, width), entry.count, entry.score);
+	}
+}
+
+fn print_ngrams(counter: &NgramCounter, args: &Args) {
+	let table = counter.top(args.top, args.options.ngram_min_count);
+	if table.is_empty() {
+		return;
+	}
+	let texts: Vec<String> = table.iter().map(Ngram::text).collect();
+	let width = texts.iter().map(|text| grapheme_count(text)).max().unwrap_or(0).max(6);
+
+	println!();
+	println!("Most frequent {}-grams (seen at least {} times):", counter.n(), args.options.ngram_min_count);
+	println!("{:>5}  {:<width$}  {:>7}  {:>7}", "rank", "n-gram", "count", "pmi");
+	for (i, (entry, text)) in table.iter().zip(texts).enumerate() {
+		println!("{:>5}  {}  {:>7}  {:>7.2}", i + 1, pad(&text, width), entry.count, entry.pmi);
+	}
+}
+
+fn print_lengths(title: &str, unit: &str, lengths: &Distribution, histogram: bool) {
+	println!(
+		"{}: mean {:.2}, median {}, 90th percentile {}, max {} {}",
+		title,
+		lengths.mean(),
+		lengths.median(),
+		lengths.percentile(90.0),
+		lengths.max(),
+		unit
+	);
+	if histogram && lengths.count() > 0 {
+		print!("{}", lengths::bar_chart(&lengths.histogram(20), 40));
+	}
+}
+
+/// Line numbers of `lines`, the first ten only.
+fn line_numbers(lines: impl Iterator<Item = usize>) -> String {
+	let numbers: Vec<String> = lines.map(|number| number.to_string()).collect();
+	if numbers.len() > 10 {
+		format!("{}, ...", numbers[..10].join(", "))
+	} else {
+		numbers.join(", ")
+	}
+}
+
+fn print_line_stats(lines: &LineStats) {
+	println!();
+	print!("Lines: {} ({} blank", lines.total(), lines.blank());
+	if let Some(run) = lines.longest_blank_run().filter(|run| run.width > 1) {
+		print!(", {} blank in a row from line {}", run.width, run.number);
+	}
+	println!(")");
+	let (lf, crlf) = lines.line_breaks();
+	match lines.endings() {
+		LineEndings::Mixed => println!("Line breaks: mixed ({} lf, {} crlf)", lf, crlf),
+		endings => println!("Line breaks: {}", endings),
+	}
+	println!(
+		"Indentation: {} lines with tabs, {} with spaces, {} mixing both",
+		lines.tab_indented(),
+		lines.space_indented(),
+		lines.mixed_indentation().len()
+	);
+	if !lines.trailing_whitespace().is_empty() {
+		println!(
+			"Trailing whitespace on {} lines: {}",
+			lines.trailing_whitespace().len(),
+			line_numbers(lines.trailing_whitespace().iter().map(|line| line.number))
+		);
+	}
+	if !lines.mixed_indentation().is_empty() {
+		println!("Mixed indentation on lines: {}", line_numbers(lines.mixed_indentation().iter().copied()));
+	}
+	if let Some(max) = lines.max_width() {
+		println!(
+			"Lines over {} characters: {}{}",
+			max,
+			lines.too_long().len(),
+			if lines.too_long().is_empty() { String::new() } else { format!(" ({})", line_numbers(lines.too_long().iter().map(|line| line.number))) }
+		);
+	}
+	if !lines.longest().is_empty() {
+		println!("Longest lines:");
+		for line in lines.longest() {
+			println!("  line {:>6}  {:>5} characters", line.number, line.width);
 		}
 	}
+}
 
-    let file = match read_to_string(path) {
-		Ok(content) => content,
-		Err(_) => {
-			println!("Failed to read the file");
-			return ;
+fn print_spelling(spelling: &Spelling) {
+	println!();
+	println!("Spelling: {} unknown words of {} checked", spelling.unknown(), spelling.checked());
+	for misspelling in spelling.misspellings() {
+		let position = format!("{}:{}", misspelling.line, misspelling.column);
+		if misspelling.suggestions.is_empty() {
+			println!("  {:>9}  {}", position, misspelling.word);
+		} else {
+			println!("  {:>9}  {} ({})", position, misspelling.word, misspelling.suggestions.join(", "));
 		}
-	};
+	}
+}
 
-	let most_used = Stats::most_used_word(&file).expect("Empty file");
-	
-	println!("There is {} characters", Stats::count_chars(&file));
-	println!("There is {} words", Stats::count_words(&file));
-	println!("The average word len is {}", Stats::word_len(&file));
+fn print_style(style: &Style) {
+	println!();
+	println!("Style: {} problems", style.problems());
+	for diagnostic in style.diagnostics() {
+		let position = format!("{}:{}", diagnostic.line, diagnostic.column);
+		println!("  {:>9}  {} [{}]", position, diagnostic.message, diagnostic.rule);
+	}
+}
+
+fn print_report(stats: &Stats, args: &Args, idf: Option<&Idf>) {
+	let most_used = stats.most_used_words();
+
+	if let Some(encoding) = stats.encoding() {
+		println!("The encoding is {}", encoding);
+	}
+	if let Some(detection) = stats.language() {
+		println!("The language looks like {} ({:.0}% confidence)", detection.language, detection.confidence * 100.0);
+	}
+	println!("There is {} characters ({} code points, {} bytes)", stats.chars(), stats.code_points(), stats.bytes());
+	println!("There is {} words", stats.words());
+	println!("The average word len is {:.2}", stats.word_len());
 	if most_used.len() == 1 {
 		println!("The most used word is '{}'", most_used[0]);
-	} else {
+	} else if most_used.len() > 1 {
 		print!("The most used word are");
-		let mut i: usize = 1;
-		for word in &most_used {
-			if i == 1 {
+		for (i, word) in most_used.iter().enumerate() {
+			if i == 0 {
 				print!(" '{}'", *word);
-			} else if i == most_used.len() {
+			} else if i + 1 == most_used.len() {
 				print!(" and '{}'", *word);
 			} else {
 				print!(", '{}'", *word);
 			}
-			i += 1;
 		}
-		println!("");
+		println!();
+	}
+	println!("There is {} phrases", stats.phrases());
+	println!("The average phrase len is {:.2} words", stats.phrases_len());
+	println!("The longest phrase has {} words", stats.max_phrase_len());
+	println!("There is {} non-empty lines", stats.lines());
+	println!("There is {} syllables", stats.syllables());
+	println!();
+	print_lengths("Word length", "characters", stats.word_lengths(), args.histogram);
+	print_lengths("Phrase length", "words", stats.phrase_lengths(), args.histogram);
+	if let Some(readability) = stats.readability() {
+		println!();
+		println!("Readability:");
+		println!("  Flesch reading ease   {:>7.2}", readability.flesch_reading_ease);
+		println!("  Flesch-Kincaid grade  {:>7.2}", readability.flesch_kincaid_grade);
+		println!("  Gunning fog index     {:>7.2}", readability.gunning_fog);
+		println!("  SMOG grade            {:>7.2}", readability.smog);
+	}
+	if !stats.metrics().is_empty() {
+		let width = stats.metrics().iter().map(|metric| grapheme_count(&metric.name)).max().unwrap_or(0);
+		println!();
+		println!("Metrics:");
+		for metric in stats.metrics() {
+			println!("  {}  {:>9.2}", pad(&metric.name, width), metric.value);
+		}
+	}
+	if let Some(lines) = stats.line_stats().filter(|_| args.lines) {
+		print_line_stats(lines);
+	}
+	if let Some(spelling) = stats.spelling() {
+		print_spelling(spelling);
+	}
+	if let Some(style) = stats.style() {
+		print_style(style);
+	}
+	print_frequencies(stats, args);
+	if let (Some(idf), Some(limit)) = (idf, args.keywords) {
+		print_keywords(stats, idf, limit);
+	}
+	for counter in stats.ngrams() {
+		print_ngrams(counter, args);
+	}
+}
+
+fn frequency_value(entry: Frequency) -> Value {
+	Value::object(vec![
+		("word", Value::Str(entry.word)),
+		("count", Value::Int(entry.count as u64)),
+		("percent", Value::Float(entry.percent)),
+	])
+}
+
+fn line_stats_value(lines: &LineStats) -> Value {
+	let list = |lines: &[Line]| Value::List(lines.iter().map(|line| Value::object(vec![
+		("line", Value::Int(line.number as u64)),
+		("width", Value::Int(line.width as u64)),
+	])).collect());
+	Value::object(vec![
+		("total", Value::Int(lines.total() as u64)),
+		("blank", Value::Int(lines.blank() as u64)),
+		("longest_blank_run", Value::Int(lines.longest_blank_run().map_or(0, |run| run.width) as u64)),
+		("line_breaks", Value::Str(lines.endings().to_string())),
+		("tab_indented", Value::Int(lines.tab_indented() as u64)),
+		("space_indented", Value::Int(lines.space_indented() as u64)),
+		("mixed_indentation", Value::List(lines.mixed_indentation().iter().map(|number| Value::Int(*number as u64)).collect())),
+		("trailing_whitespace", Value::List(lines.trailing_whitespace().iter().map(|line| Value::Int(line.number as u64)).collect())),
+		("longest", list(lines.longest())),
+		("too_long", list(lines.too_long())),
+	])
+}
+
+fn spelling_value(spelling: &Spelling) -> Value {
+	Value::object(vec![
+		("checked", Value::Int(spelling.checked() as u64)),
+		("unknown", Value::Int(spelling.unknown() as u64)),
+		("misspellings", Value::List(spelling.misspellings().iter().map(|misspelling| Value::object(vec![
+			("line", Value::Int(misspelling.line as u64)),
+			("column", Value::Int(misspelling.column as u64)),
+			("word", Value::Str(misspelling.word.clone())),
+			("suggestions", Value::List(misspelling.suggestions.iter().cloned().map(Value::Str).collect())),
+		])).collect())),
+	])
+}
+
+fn style_value(style: &Style) -> Value {
+	Value::object(vec![
+		("problems", Value::Int(style.problems() as u64)),
+		("diagnostics", Value::List(style.diagnostics().iter().map(|diagnostic| Value::object(vec![
+			("line", Value::Int(diagnostic.line as u64)),
+			("column", Value::Int(diagnostic.column as u64)),
+			("rule", Value::Str(diagnostic.rule.to_string())),
+			("message", Value::Str(diagnostic.message.clone())),
+		])).collect())),
+	])
+}
+
+fn keywords_value(keywords: Vec<Keyword>) -> Value {
+	Value::List(keywords.into_iter().map(|keyword| Value::object(vec![
+		("word", Value::Str(keyword.word)),
+		("count", Value::Int(keyword.count as u64)),
+		("score", Value::Float(keyword.score)),
+	])).collect())
+}
+
+fn ngrams_value(counter: &NgramCounter, args: &Args) -> Value {
+	let table = counter.top(args.top, args.options.ngram_min_count);
+	Value::object(vec![
+		("n", Value::Int(counter.n() as u64)),
+		("total", Value::Int(counter.total() as u64)),
+		("top", Value::List(table.into_iter().map(|entry| Value::object(vec![
+			("ngram", Value::Str(entry.text())),
+			("count", Value::Int(entry.count as u64)),
+			("pmi", Value::Float(entry.pmi)),
+		])).collect())),
+	])
+}
+
+fn readability_value(readability: Option<Readability>) -> Value {
+	match readability {
+		Some(readability) => Value::object(vec![
+			("flesch_reading_ease", Value::Float(readability.flesch_reading_ease)),
+			("flesch_kincaid_grade", Value::Float(readability.flesch_kincaid_grade)),
+			("gunning_fog", Value::Float(readability.gunning_fog)),
+			("smog", Value::Float(readability.smog)),
+		]),
+		None => Value::object(vec![
+			("flesch_reading_ease", Value::Float(f64::NAN)),
+			("flesch_kincaid_grade", Value::Float(f64::NAN)),
+			("gunning_fog", Value::Float(f64::NAN)),
+			("smog", Value::Float(f64::NAN)),
+		]),
+	}
+}
+
+fn lengths_value(lengths: &Distribution) -> Value {
+	let histogram = lengths
+		.counts()
+		.iter()
+		.enumerate()
+		.filter(|(_, count)| **count > 0)
+		.map(|(length, count)| Value::object(vec![
+			("length", Value::Int(length as u64)),
+			("count", Value::Int(*count as u64)),
+		]))
+		.collect();
+
+	Value::object(vec![
+		("mean", Value::Float(lengths.mean())),
+		("median", Value::Float(lengths.median())),
+		("p25", Value::Int(lengths.percentile(25.0) as u64)),
+		("p75", Value::Int(lengths.percentile(75.0) as u64)),
+		("p90", Value::Int(lengths.percentile(90.0) as u64)),
+		("max", Value::Int(lengths.max() as u64)),
+		("histogram", Value::List(histogram)),
+	])
+}
+
+/// Every figure of the text report, under the field names used by the
+/// json and csv formats.
+fn build_report(name: &str, stats: &Stats, args: &Args, idf: Option<&Idf>) -> Value {
+	let frequencies = stats.frequencies().top(args.top, args.order);
+
+	let mut fields = vec![
+		("file", Value::Str(name.to_string())),
+		("encoding", Value::Str(stats.encoding().map(|encoding| encoding.to_string()).unwrap_or_default())),
+		("language", Value::Str(stats.language().map(|detection| detection.language.code().to_string()).unwrap_or_default())),
+		("language_confidence", Value::Float(stats.language().map(|detection| detection.confidence).unwrap_or(f64::NAN))),
+		("chars", Value::Int(stats.chars() as u64)),
+		("code_points", Value::Int(stats.code_points() as u64)),
+		("bytes", Value::Int(stats.bytes() as u64)),
+		("words", Value::Int(stats.words() as u64)),
+		("phrases", Value::Int(stats.phrases() as u64)),
+		("non_empty_lines", Value::Int(stats.lines() as u64)),
+		("average_word_len", Value::Float(stats.word_len())),
+		("average_phrase_len", Value::Float(stats.phrases_len())),
+		("max_phrase_len", Value::Int(stats.max_phrase_len() as u64)),
+		("word_lengths", lengths_value(stats.word_lengths())),
+		("phrase_lengths", lengths_value(stats.phrase_lengths())),
+		("syllables", Value::Int(stats.syllables() as u64)),
+		("readability", readability_value(stats.readability())),
+		("most_used_words", Value::List(stats.most_used_words().iter().cloned().map(Value::Str).collect())),
+		("most_used_count", Value::Int(stats.most_used_count() as u64)),
+		("distinct_words", Value::Int(stats.frequencies().distinct() as u64)),
+		("frequencies", Value::List(frequencies.into_iter().map(frequency_value).collect())),
+		("ngrams", Value::List(stats.ngrams().iter().map(|counter| ngrams_value(counter, args)).collect())),
+	];
+	if !stats.metrics().is_empty() {
+		let metrics = stats.metrics().iter().map(|metric| (metric.name.clone(), Value::Float(metric.value))).collect();
+		fields.push(("metrics", Value::Object(metrics)));
+	}
+	if let Some(lines) = stats.line_stats().filter(|_| args.lines) {
+		fields.push(("lines", line_stats_value(lines)));
+	}
+	if let Some(spelling) = stats.spelling() {
+		fields.push(("spelling", spelling_value(spelling)));
+	}
+	if let Some(style) = stats.style() {
+		fields.push(("style", style_value(style)));
+	}
+	if let (Some(idf), Some(limit)) = (idf, args.keywords) {
+		let limit = if limit == 0 { None } else { Some(limit) };
+		fields.push(("keywords", keywords_value(idf.keywords(stats.frequencies(), limit))));
+	}
+	Value::object(fields)
+}
+
+fn print_comparison(left: (&str, &Stats), right: (&str, &Stats), comparison: &Comparison, args: &Args) {
+	let limit = args.top.unwrap_or(usize::MAX);
+	let list = |words: &[String]| words.iter().take(limit).map(|word| format!("'{}'", word)).collect::<Vec<String>>().join(", ");
+
+	println!("==> {} <==> {} <==", left.0, right.0);
+	println!(
+		"There is {} shared words, {} only in the first text and {} only in the second",
+		comparison.shared.len(),
+		comparison.only_left.len(),
+		comparison.only_right.len()
+	);
+	println!("Jaccard similarity  {:.3}", comparison.jaccard);
+	println!("Cosine similarity   {:.3}", comparison.cosine);
+	if !comparison.shared.is_empty() {
+		println!("Shared: {}", list(&comparison.shared));
+	}
+	if !comparison.only_left.is_empty() {
+		println!("Only in the first: {}", list(&comparison.only_left));
+	}
+	if !comparison.only_right.is_empty() {
+		println!("Only in the second: {}", list(&comparison.only_right));
+	}
+
+	println!();
+	println!("{:<22}  {:>12}  {:>12}  {:>12}", "metric", "first", "second", "difference");
+	for ((name, a), (_, b)) in compare::metrics(left.1).into_iter().zip(compare::metrics(right.1)) {
+		println!("{:<22}  {:>12.2}  {:>12.2}  {:>+12.2}", name, a, b, b - a);
+	}
+}
+
+fn comparison_value(left: (&str, &Stats), right: (&str, &Stats), comparison: Comparison, args: &Args) -> Value {
+	let limit = args.top.unwrap_or(usize::MAX);
+	let list = |words: Vec<String>| Value::List(words.into_iter().take(limit).map(Value::Str).collect());
+	let metrics = compare::metrics(left.1)
+		.into_iter()
+		.zip(compare::metrics(right.1))
+		.map(|((name, a), (_, b))| Value::object(vec![
+			("metric", Value::Str(name.replace([' ', '-'], "_"))),
+			("first", Value::Float(a)),
+			("second", Value::Float(b)),
+			("difference", Value::Float(b - a)),
+		]))
+		.collect();
+
+	Value::object(vec![
+		("first", Value::Str(left.0.to_string())),
+		("second", Value::Str(right.0.to_string())),
+		("shared_words", Value::Int(comparison.shared.len() as u64)),
+		("only_first_words", Value::Int(comparison.only_left.len() as u64)),
+		("only_second_words", Value::Int(comparison.only_right.len() as u64)),
+		("jaccard", Value::Float(comparison.jaccard)),
+		("cosine", Value::Float(comparison.cosine)),
+		("shared", list(comparison.shared)),
+		("only_first", list(comparison.only_left)),
+		("only_second", list(comparison.only_right)),
+		("metrics", Value::List(metrics)),
+	])
+}
+
+/// Prints the problems found in every input, one per line.
+fn print_lint(analysed: &[(String, Stats)], args: &Args) {
+	let diagnostics = analysed.iter().flat_map(|(name, stats)| {
+		stats.diagnostics().into_iter().map(move |diagnostic| (name, diagnostic))
+	});
+	match args.format {
+		Format::Text => {
+			for (name, diagnostic) in diagnostics {
+				println!("{}:{}", name, diagnostic);
+			}
+		}
+		Format::Json | Format::Csv => {
+			let values: Vec<Value> = diagnostics
+				.map(|(name, diagnostic)| Value::object(vec![
+					("file", Value::Str(name.clone())),
+					("line", Value::Int(diagnostic.line as u64)),
+					("column", Value::Int(diagnostic.column as u64)),
+					("rule", Value::Str(diagnostic.rule.to_string())),
+					("message", Value::Str(diagnostic.message)),
+				]))
+				.collect();
+			match args.format {
+				Format::Json => println!("{}", report::to_json(&Value::List(values))),
+				_ => print!("{}", report::to_csv(&values)),
+			}
+		}
+	}
+}
+
+/// Document frequencies of the analysed inputs, added to the ones loaded
+/// with `--idf`.
+fn corpus_idf(analysed: &[(String, Stats)], args: &Args) -> Idf {
+	let mut idf = args.idf.clone().unwrap_or_default();
+	for (_, stats) in analysed {
+		idf.add_document(stats.frequencies());
+	}
+	idf
+}
+
+/// Prints the report of every analysed input and their total in the chosen
+/// format. In text, `changes` are shown under the report of the input at
+/// the same index.
+fn print_output(analysed: &[(String, Stats)], args: &Args, changes: &[String]) {
+	if args.lint {
+		print_lint(analysed, args);
+		return;
+	}
+	let idf = args.keywords.map(|_| corpus_idf(analysed, args));
+	let several = analysed.len() > 1;
+	let total = if several { Stats::total(analysed.iter().map(|(_, stats)| stats)) } else { None };
+	match args.format {
+		Format::Text => {
+			for (i, (name, stats)) in analysed.iter().enumerate() {
+				if several {
+					if i > 0 {
+						println!();
+					}
+					println!("==> {} <==", name);
+				}
+				print_report(stats, args, idf.as_ref());
+				if let Some(change) = changes.get(i).filter(|change| !change.is_empty()) {
+					println!();
+					println!("Since the last run: {}", change);
+				}
+			}
+			if let Some(total) = &total {
+				println!();
+				println!("==> total of {} files <==", analysed.len());
+				print_report(total, args, None);
+			}
+		}
+		Format::Json => {
+			// The same shape for any number of inputs: the total of a single
+			// one is its own report.
+			let files = analysed.iter().map(|(name, stats)| build_report(name, stats, args, idf.as_ref())).collect();
+			let mut fields = vec![("files", Value::List(files))];
+			if let Some(total) = total.or_else(|| Stats::total(analysed.iter().map(|(_, stats)| stats))) {
+				fields.push(("total", build_report("total", &total, args, None)));
+			}
+			println!("{}", report::to_json(&Value::object(fields)));
+		}
+		Format::Csv => {
+			let mut reports: Vec<Value> = analysed.iter().map(|(name, stats)| build_report(name, stats, args, idf.as_ref())).collect();
+			if let Some(total) = &total {
+				reports.push(build_report("total", total, args, None));
+			}
+			print!("{}", report::to_csv(&reports));
+		}
+	}
+}
+
+/// Prints `error` and exits with its status.
+fn fail(error: Error) -> ! {
+	eprintln!("{}", error);
+	process::exit(error.exit_code());
+}
+
+fn main() {
+	let command_line: Vec<String> = env::args().skip(1).collect();
+	let mut args = Args::new();
+	let configs = cli::load_configs(&command_line).unwrap_or_else(|message| fail(Error::Usage(message)));
+	for config in &configs {
+		if let Err(message) = args.apply_config(config) {
+			fail(Error::Usage(message));
+		}
+	}
+	if let Err(message) = args.read(command_line.into_iter()) {
+		let error = Error::Usage(message);
+		eprintln!("{}", error);
+		eprintln!("{}", USAGE);
+		process::exit(error.exit_code());
+	}
+	if args.help {
+		println!("{}", USAGE);
+		return ;
+	}
+	if args.paths.is_empty() {
+		match prompt_path() {
+			Ok(path) => args.paths.push(path),
+			Err(error) => fail(error),
+		}
+	}
+
+	let (inputs, mut errors) = cli::collect_inputs(&args.paths, &args.filter, check_path);
+	let results = corpus::parallel_map(&inputs, args.jobs, |input| analyse_input(input, &args));
+	if args.watch {
+		if args.compare {
+			fail(Error::Usage("Watching and comparing cannot be used together".to_string()));
+		}
+		watch::run(inputs, results, errors, &args);
+	}
+	let mut analysed: Vec<(String, Stats)> = Vec::new();
+	for (input, result) in inputs.iter().zip(results) {
+		match result {
+			Ok(stats) => analysed.push((input.name(), stats)),
+			Err(error) => errors.push(error),
+		}
+	}
+	for error in &errors {
+		eprintln!("{}", error);
+	}
+	let status = errors.first().map(Error::exit_code);
+	if analysed.is_empty() {
+		process::exit(status.unwrap_or(Error::EmptyInput(String::new()).exit_code()));
+	}
+
+	if args.compare {
+		if analysed.len() != 2 {
+			fail(Error::Usage(format!("Comparing needs exactly two inputs, {} given", analysed.len())));
+		}
+		let left = (analysed[0].0.as_str(), &analysed[0].1);
+		let right = (analysed[1].0.as_str(), &analysed[1].1);
+		let comparison = compare::compare(left.1.frequencies(), right.1.frequencies());
+		match args.format {
+			Format::Text => print_comparison(left, right, &comparison, &args),
+			Format::Json => println!("{}", report::to_json(&comparison_value(left, right, comparison, &args))),
+			Format::Csv => print!("{}", report::to_csv(&[comparison_value(left, right, comparison, &args)])),
+		}
+		if let Some(status) = status {
+			process::exit(status);
+		}
+		return ;
+	}
+
+	let mut too_hard = Vec::new();
+	for (name, stats) in &analysed {
+		if let (Some(max), Some(readability)) = (args.max_grade, stats.readability()) {
+			if readability.flesch_kincaid_grade > max {
+				too_hard.push((name, readability.flesch_kincaid_grade));
+			}
+		}
+	}
+
+	print_output(&analysed, &args, &[]);
+	if let Some(path) = &args.save_idf {
+		if let Err(error) = corpus_idf(&analysed, &args).save(path) {
+			fail(Error::from_io(&path.display().to_string(), error));
+		}
+	}
+
+	if let Some(max) = args.max_grade {
+		for (name, grade) in &too_hard {
+			eprintln!("{}: reading grade {:.2} is above {}", name, grade, max);
+		}
+	}
+	if let Some(status) = status {
+		process::exit(status);
+	}
+	let problems = args.lint && analysed.iter().any(|(_, stats)| !stats.diagnostics().is_empty());
+	if !too_hard.is_empty() || problems {
+		process::exit(1);
 	}
-	println!("There is {} phrases", Stats::count_phrases(&file));
-	println!("There is {} non-empty lines", Stats::count_lines(&file));
 }
@@ -0,0 +1,110 @@
+
+use std::{cmp::Ordering, collections::{HashMap, VecDeque}};
+
+/// A sequence of words and how strongly they are associated.
+pub struct Ngram {
+	pub words: Vec<String>,
+	pub count: usize,
+	/// Pointwise mutual information, in bits: how much more often the words
+	/// appear together than if they were independent.
+	pub pmi: f64,
+}
+
+impl Ngram {
+	pub fn text(&self) -> String {
+		self.words.join(" ")
+	}
+}
+
+/// Counts the sequences of `n` consecutive words of a text.
+#[derive(Clone)]
+pub struct NgramCounter {
+	n: usize,
+	window: VecDeque<String>,
+	counts: HashMap<Vec<String>, usize>,
+	unigrams: HashMap<String, usize>,
+	words: usize,
+	total: usize,
+}
+
+impl NgramCounter {
+	pub fn new(n: usize) -> NgramCounter {
+		NgramCounter {
+			n: n.max(1),
+			window: VecDeque::new(),
+			counts: HashMap::new(),
+			unigrams: HashMap::new(),
+			words: 0,
+			total: 0,
+		}
+	}
+
+	pub fn n(&self) -> usize {
+		self.n
+	}
+
+	pub fn push(&mut self, word: &str) {
+		*self.unigrams.entry(word.to_string()).or_insert(0) += 1;
+		self.words += 1;
+
+		self.window.push_back(word.to_string());
+		if self.window.len() > self.n {
+			self.window.pop_front();
+		}
+		if self.window.len() == self.n {
+			let key: Vec<String> = self.window.iter().cloned().collect();
+			*self.counts.entry(key).or_insert(0) += 1;
+			self.total += 1;
+		}
+	}
+
+	/// Starts a new sequence: no n-gram spans over a reset, which is used at
+	/// the end of phrases and around filtered words.
+	pub fn reset(&mut self) {
+		self.window.clear();
+	}
+
+	/// Adds the counts of `other`, which must count n-grams of the same size.
+	pub fn merge(&mut self, other: &NgramCounter) {
+		for (words, count) in &other.counts {
+			*self.counts.entry(words.clone()).or_insert(0) += count;
+		}
+		for (word, count) in &other.unigrams {
+			*self.unigrams.entry(word.clone()).or_insert(0) += count;
+		}
+		self.words += other.words;
+		self.total += other.total;
+	}
+
+	/// Number of n-grams counted, repetitions included.
+	pub fn total(&self) -> usize {
+		self.total
+	}
+
+	fn pmi(&self, words: &[String], count: usize) -> f64 {
+		let joint = count as f64 / self.total as f64;
+		let independent: f64 = words
+			.iter()
+			.map(|word| self.unigrams.get(word).copied().unwrap_or(0) as f64 / self.words as f64)
+			.product();
+		(joint / independent).log2()
+	}
+
+	/// The `limit` most frequent n-grams seen at least `min_count` times,
+	/// or all of them without a limit. Ties are sorted by decreasing PMI.
+	pub fn top(&self, limit: Option<usize>, min_count: usize) -> Vec<Ngram> {
+		let mut result: Vec<Ngram> = self.counts
+			.iter()
+			.filter(|(_, count)| **count >= min_count)
+			.map(|(words, count)| Ngram { words: words.clone(), count: *count, pmi: self.pmi(words, *count) })
+			.collect();
+		result.sort_by(|a, b| {
+			b.count
+				.cmp(&a.count)
+				.then(b.pmi.partial_cmp(&a.pmi).unwrap_or(Ordering::Equal))
+				.then_with(|| a.words.cmp(&b.words))
+		});
+		result.truncate(limit.unwrap_or(usize::MAX));
+		result
+	}
+}
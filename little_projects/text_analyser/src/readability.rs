@@ -0,0 +1,79 @@
+
+use crate::language::Language;
+
+fn is_vowel(c: char, language: Language) -> bool {
+	match language {
+		Language::English => "aeiouy".contains(c),
+		Language::French => "aeiouyàâäéèêëîïôöûùüÿœæ".contains(c),
+		Language::Spanish => "aeiouáéíóúü".contains(c),
+		Language::German => "aeiouyäöü".contains(c),
+	}
+}
+
+/// Estimates the number of syllables of a word by counting its groups of
+/// vowels, with the usual silent endings of the language taken away. The
+/// estimate is at least 1 for any word with a letter.
+pub fn syllables(word: &str, language: Language) -> usize {
+	let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).flat_map(char::to_lowercase).collect();
+	if letters.is_empty() {
+		return 0;
+	}
+
+	let mut count = 0;
+	let mut previous_vowel = false;
+	for c in &letters {
+		let vowel = is_vowel(*c, language);
+		if vowel && !previous_vowel {
+			count += 1;
+		}
+		previous_vowel = vowel;
+	}
+
+	let ending: String = letters.iter().rev().take(3).rev().collect();
+	let silent = match language {
+		Language::English => {
+			(ending.ends_with('e') && !ending.ends_with("le") && !ending.ends_with("ee"))
+				|| (ending.ends_with("es") && !ending.ends_with("ses") && !ending.ends_with("ces"))
+				|| (ending.ends_with("ed") && !ending.ends_with("ted") && !ending.ends_with("ded"))
+		}
+		Language::French => ending.ends_with('e') || ending.ends_with("es") || ending.ends_with("ent"),
+		Language::Spanish | Language::German => false,
+	};
+	if silent && count > 1 {
+		count -= 1;
+	}
+	count.max(1)
+}
+
+/// The standard readability indices. Their formulas were designed for
+/// English and give rough figures on other languages.
+#[derive(Clone, Copy)]
+pub struct Readability {
+	/// From 0 (very hard) to 100 (very easy), can go beyond both bounds.
+	pub flesch_reading_ease: f64,
+	/// U.S. school grade needed to understand the text.
+	pub flesch_kincaid_grade: f64,
+	pub gunning_fog: f64,
+	pub smog: f64,
+}
+
+impl Readability {
+	/// Computes the indices from the counts of a text, `polysyllables`
+	/// being the words of three syllables or more. Returns `None` for a text
+	/// without any word or sentence.
+	pub fn compute(words: usize, sentences: usize, syllables: usize, polysyllables: usize) -> Option<Readability> {
+		if words == 0 || sentences == 0 {
+			return None;
+		}
+		let words_per_sentence = words as f64 / sentences as f64;
+		let syllables_per_word = syllables as f64 / words as f64;
+		let polysyllables_per_word = polysyllables as f64 / words as f64;
+
+		Some(Readability {
+			flesch_reading_ease: 206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word,
+			flesch_kincaid_grade: 0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59,
+			gunning_fog: 0.4 * (words_per_sentence + 100.0 * polysyllables_per_word),
+			smog: 1.0430 * (polysyllables as f64 * 30.0 / sentences as f64).sqrt() + 3.1291,
+		})
+	}
+}
@@ -0,0 +1,170 @@
+
+use std::{fmt::Write, str::FromStr};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+	Text,
+	Json,
+	Csv,
+}
+
+impl FromStr for Format {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().as_str() {
+			"text" => Ok(Format::Text),
+			"json" => Ok(Format::Json),
+			"csv" => Ok(Format::Csv),
+			_ => Err(format!("unknown format '{}' (expected text, json or csv)", s)),
+		}
+	}
+}
+
+/// A figure of a report, kept generic so every format is rendered from the
+/// same data.
+#[derive(Clone)]
+pub enum Value {
+	Int(u64),
+	Float(f64),
+	Str(String),
+	List(Vec<Value>),
+	Object(Vec<(String, Value)>),
+}
+
+impl Value {
+	pub fn object(fields: Vec<(&str, Value)>) -> Value {
+		Value::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+	}
+}
+
+fn escape_json(text: &str, out: &mut String) {
+	out.push('"');
+	for c in text.chars() {
+		match c {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\r' => out.push_str("\\r"),
+			'\t' => out.push_str("\\t"),
+			c if (c as u32) < 0x20 => {
+				let _ = write!(out, "\\u{:04x}", c as u32);
+			}
+			c => out.push(c),
+		}
+	}
+	out.push('"');
+}
+
+fn write_json(value: &Value, out: &mut String) {
+	match value {
+		Value::Int(n) => {
+			let _ = write!(out, "{}", n);
+		}
+		Value::Float(n) if n.is_finite() => {
+			let _ = write!(out, "{}", (n * 1000.0).round() / 1000.0);
+		}
+		Value::Float(_) => out.push_str("null"),
+		Value::Str(text) => escape_json(text, out),
+		Value::List(items) => {
+			out.push('[');
+			for (i, item) in items.iter().enumerate() {
+				if i > 0 {
+					out.push(',');
+				}
+				write_json(item, out);
+			}
+			out.push(']');
+		}
+		Value::Object(fields) => {
+			out.push('{');
+			for (i, (key, item)) in fields.iter().enumerate() {
+				if i > 0 {
+					out.push(',');
+				}
+				escape_json(key, out);
+				out.push(':');
+				write_json(item, out);
+			}
+			out.push('}');
+		}
+	}
+}
+
+pub fn to_json(value: &Value) -> String {
+	let mut out = String::new();
+	write_json(value, &mut out);
+	out
+}
+
+fn csv_cell(value: &Value) -> String {
+	match value {
+		Value::Int(n) => n.to_string(),
+		Value::Float(n) if n.is_finite() => ((n * 1000.0).round() / 1000.0).to_string(),
+		Value::Float(_) => String::new(),
+		Value::Str(text) => text.clone(),
+		Value::List(items) => items.iter().map(csv_cell).collect::<Vec<String>>().join(";"),
+		Value::Object(fields) => fields
+			.iter()
+			.map(|(_, item)| csv_cell(item))
+			.collect::<Vec<String>>()
+			.join(":"),
+	}
+}
+
+fn escape_csv(cell: &str) -> String {
+	if cell.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", cell.replace('"', "\"\""))
+	} else {
+		cell.to_string()
+	}
+}
+
+/// Flattens nested objects into `parent_child` columns.
+fn csv_columns(prefix: &str, value: &Value, columns: &mut Vec<(String, String)>) {
+	match value {
+		Value::Object(fields) => {
+			for (key, item) in fields {
+				let name = if prefix.is_empty() { key.clone() } else { format!("{}_{}", prefix, key) };
+				csv_columns(&name, item, columns);
+			}
+		}
+		other => columns.push((prefix.to_string(), csv_cell(other))),
+	}
+}
+
+/// Renders the reports as a CSV table, one row per report. The header has
+/// every column of the reports, in the order they first appear; a report
+/// without one of them leaves its cell empty.
+pub fn to_csv(reports: &[Value]) -> String {
+	let rows: Vec<Vec<(String, String)>> = reports
+		.iter()
+		.map(|report| {
+			let mut columns = Vec::new();
+			csv_columns("", report, &mut columns);
+			columns
+		})
+		.collect();
+	let mut header: Vec<&str> = Vec::new();
+	for (name, _) in rows.iter().flatten() {
+		if !header.contains(&name.as_str()) {
+			header.push(name);
+		}
+	}
+
+	let mut out = String::new();
+	if !rows.is_empty() {
+		let names: Vec<String> = header.iter().map(|name| escape_csv(name)).collect();
+		out.push_str(&names.join(","));
+		out.push('\n');
+	}
+	for columns in &rows {
+		let row: Vec<String> = header
+			.iter()
+			.map(|name| columns.iter().find(|(column, _)| column == name).map(|(_, cell)| escape_csv(cell)).unwrap_or_default())
+			.collect();
+		out.push_str(&row.join(","));
+		out.push('\n');
+	}
+	out
+}
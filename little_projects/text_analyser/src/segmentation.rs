@@ -0,0 +1,17 @@
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The words of `text`, found with the Unicode word boundaries (UAX #29):
+/// "don't" and "l'été" are one word, each CJK ideograph is a word, and
+/// punctuation is never part of a word. Numbers and other sequences without
+/// a letter are left out.
+pub fn words(text: &str) -> impl Iterator<Item = &str> {
+	text.unicode_words().filter(|word| word.contains(|c: char| c.is_alphabetic()))
+}
+
+/// Number of user-perceived characters (extended grapheme clusters) of
+/// `text`: "é" is one character whether it is written with one or two code
+/// points.
+pub fn grapheme_count(text: &str) -> usize {
+	text.graphemes(true).count()
+}
@@ -0,0 +1,257 @@
+
+use crate::{language::Language, lengths::Distribution};
+
+const CLOSERS: &[char] = &['"', '\'', '”', '’', '»', ')', ']', '}'];
+const OPENERS: &[char] = &['"', '\'', '“', '‘', '«', '(', '[', '{', '¿', '¡'];
+
+/// Abbreviations that are never the end of a sentence, written without
+/// their final dot.
+fn abbreviations(language: Language) -> &'static [&'static str] {
+	match language {
+		Language::English => &[
+			"mr", "mrs", "ms", "dr", "prof", "sr", "st", "vs", "e.g", "i.e", "cf", "fig", "vol", "pp", "approx",
+			"dept", "gov", "feb", "apr", "jun", "jul", "aug", "sep", "sept", "oct", "nov", "dec", "mt", "ave",
+			"blvd", "p", "ch",
+		],
+		Language::French => &[
+			"m", "mm", "mme", "mmes", "mlle", "dr", "pr", "st", "ste", "cf", "p", "ex", "p.ex", "env", "av",
+			"bd", "n°", "janv", "févr", "avr", "juil", "sept", "oct", "nov", "déc", "chap", "éd", "vol",
+		],
+		Language::Spanish => &[
+			"sr", "sra", "srta", "dr", "dra", "ud", "uds", "p.ej", "pág", "núm", "av", "avda", "dña", "d",
+			"ene", "feb", "abr", "jun", "jul", "ago", "sept", "oct", "nov", "dic", "cap", "vol",
+		],
+		Language::German => &[
+			"z.b", "bzw", "ca", "dr", "prof", "nr", "str", "evtl", "ggf", "d.h", "u.a", "vgl", "s", "hr",
+			"fr", "jan", "feb", "mär", "apr", "jun", "jul", "aug", "sep", "okt", "nov", "dez", "bd", "abs",
+		],
+	}
+}
+
+/// Abbreviations that end a sentence only when the next word starts with
+/// a capital letter, among them the ones which are also words, as "no" or
+/// "mar".
+fn ambiguous_abbreviations(language: Language) -> &'static [&'static str] {
+	match language {
+		Language::English => &[
+			"etc", "inc", "ltd", "co", "corp", "jr", "u.s", "u.k", "a.m", "p.m", "no", "ed", "al", "est", "gen",
+			"jan", "mar",
+		],
+		Language::French => &["etc", "cie", "j.-c"],
+		Language::Spanish => &["etc", "cía", "s.a", "mar"],
+		Language::German => &["usw", "etc", "gmbh", "u.s.w"],
+	}
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum End {
+	#[default]
+	No,
+	Yes,
+	/// Decided by the next token.
+	Maybe,
+	/// Decided by the next token starting with a capital letter, after an
+	/// abbreviation: "No. 5" goes on.
+	Capital,
+}
+
+/// Finds the sentences of a text given one whitespace separated token at a
+/// time. A sentence ends with '?', '!' or '.', except in abbreviations,
+/// initials and numbers like "3.50"; an ellipsis or a terminator inside
+/// quotes or brackets ends it only when the next word starts with a
+/// capital. A blank line always ends a sentence.
+#[derive(Clone, Default)]
+pub struct SentenceSegmenter {
+	language: Language,
+	words: usize,
+	started: bool,
+	pending: End,
+	lengths: Distribution,
+}
+
+fn starts_sentence(token: &str) -> bool {
+	match token.trim_start_matches(OPENERS).chars().next() {
+		Some(c) => !c.is_lowercase(),
+		None => true,
+	}
+}
+
+impl SentenceSegmenter {
+	pub fn new(language: Language) -> SentenceSegmenter {
+		SentenceSegmenter { language, ..SentenceSegmenter::default() }
+	}
+
+	fn ending(&self, token: &str) -> End {
+		let core = token.trim_end_matches(CLOSERS);
+		let quoted = core.len() != token.len();
+
+		if core.ends_with("...") || core.ends_with('…') {
+			return End::Maybe;
+		}
+		if core.ends_with(['?', '!']) {
+			return if quoted { End::Maybe } else { End::Yes };
+		}
+		if !core.ends_with('.') {
+			return End::No;
+		}
+
+		let word = core.trim_end_matches('.').trim_start_matches(OPENERS).to_lowercase();
+		let language = self.language;
+		if abbreviations(language).contains(&word.as_str()) {
+			return End::No;
+		}
+		let mut letters = word.chars();
+		if let (Some(c), None) = (letters.next(), letters.next()) {
+			if c.is_alphabetic() {
+				// An initial, as in "J. Smith".
+				return End::No;
+			}
+		}
+		if ambiguous_abbreviations(language).contains(&word.as_str()) {
+			return End::Capital;
+		}
+		if word.contains('.') || quoted {
+			return End::Maybe;
+		}
+		End::Yes
+	}
+
+	fn close(&mut self) {
+		if self.started {
+			self.lengths.add(self.words);
+		}
+		self.words = 0;
+		self.started = false;
+		self.pending = End::No;
+	}
+
+	/// Adds the next token of the text, made of `words` words, and returns
+	/// whether a sentence ended just before it.
+	pub fn push(&mut self, token: &str, words: usize) -> bool {
+		let boundary = match self.pending {
+			End::No => false,
+			End::Yes => true,
+			End::Maybe => starts_sentence(token),
+			End::Capital => token.trim_start_matches(OPENERS).chars().next().is_some_and(char::is_uppercase),
+		};
+		if boundary {
+			self.close();
+		}
+		if token.contains(|c: char| c.is_alphanumeric()) {
+			self.started = true;
+		}
+		self.words += words;
+		self.pending = self.ending(token);
+		boundary
+	}
+
+	/// Ends the current sentence, at a blank line.
+	pub fn paragraph_break(&mut self) {
+		self.close();
+	}
+
+	/// Returns the length, in words, of every sentence.
+	pub fn finish(mut self) -> Distribution {
+		self.close();
+		self.lengths
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn count(text: &str, language: Language) -> usize {
+		let mut segmenter = SentenceSegmenter::new(language);
+		for paragraph in text.split("\n\n") {
+			for token in paragraph.split_whitespace() {
+				segmenter.push(token, 1);
+			}
+			segmenter.paragraph_break();
+		}
+		segmenter.finish().count()
+	}
+
+	fn english(text: &str) -> usize {
+		count(text, Language::English)
+	}
+
+	#[test]
+	fn terminators() {
+		assert_eq!(english("One. Two! Three? Four"), 4);
+		assert_eq!(english("Is it?! Yes."), 2);
+		assert_eq!(english(""), 0);
+		assert_eq!(english("No terminator at all"), 1);
+	}
+
+	#[test]
+	fn abbreviations_and_initials() {
+		assert_eq!(english("Mr. Smith met Dr. Jones. They talked."), 2);
+		assert_eq!(english("See fig. 3 and vol. 2 of the report."), 1);
+		assert_eq!(english("J. R. R. Tolkien wrote it. It sold."), 2);
+		assert_eq!(english("Use e.g. this one. Or that."), 2);
+	}
+
+	#[test]
+	fn ambiguous_abbreviations() {
+		assert_eq!(english("Apples, pears etc. are fruits."), 1);
+		assert_eq!(english("Apples, pears etc. Fruits are good."), 2);
+		assert_eq!(english("The answer is no. We left early."), 2);
+		assert_eq!(english("See No. 5 for details."), 1);
+		assert_eq!(english("His name is Ed. He is tall."), 2);
+		assert_eq!(english("I love Jan. She is kind."), 2);
+		assert_eq!(english("It was Jan. 5 in Paris."), 1);
+		assert_eq!(english("Smith et al. showed it."), 1);
+		assert_eq!(english("He works at Acme Inc. Since May."), 2);
+	}
+
+	#[test]
+	fn numbers() {
+		assert_eq!(english("It costs 3.50 dollars. That is cheap."), 2);
+		assert_eq!(english("Version 1.2.3 is out. Update now."), 2);
+	}
+
+	#[test]
+	fn ellipses() {
+		assert_eq!(english("Well... we do not know."), 1);
+		assert_eq!(english("Wait... Then it happened."), 2);
+		assert_eq!(english("Wait… Then it happened."), 2);
+	}
+
+	#[test]
+	fn quotes_and_brackets() {
+		assert_eq!(english("He said \"Stop.\" Then he left."), 2);
+		assert_eq!(english("\"Stop!\" he said."), 1);
+		assert_eq!(english("(It was late.) We went home."), 2);
+		assert_eq!(english("He asked «why?» and left."), 1);
+		assert_eq!(english("She said 'no.' \"Fine,\" he said."), 2);
+	}
+
+	#[test]
+	fn paragraphs() {
+		assert_eq!(english("A title\n\nThe text starts here."), 2);
+		let mut segmenter = SentenceSegmenter::new(Language::English);
+		for token in ["A", "title"] {
+			segmenter.push(token, 1);
+		}
+		segmenter.paragraph_break();
+		assert!(!segmenter.push("The", 1));
+		for token in ["text", "ends."] {
+			segmenter.push(token, 1);
+		}
+		assert!(segmenter.push("Again.", 1));
+		let lengths = segmenter.finish();
+		assert_eq!((lengths.count(), lengths.sum(), lengths.max()), (3, 6, 3));
+	}
+
+	#[test]
+	fn languages() {
+		assert_eq!(count("M. Dupont est là. Il attend.", Language::French), 2);
+		assert_eq!(english("M. Dupont est là. Il attend."), 2);
+		assert_eq!(count("Sr. García llegó. Luego se fue.", Language::Spanish), 2);
+		assert_eq!(count("Fuimos al mar. Luego comimos.", Language::Spanish), 2);
+		assert_eq!(count("Das ist z.B. gut. Wir gehen.", Language::German), 2);
+		assert_eq!(english("Das ist usw. gut."), 2);
+		assert_eq!(count("Das ist usw. gut.", Language::German), 1);
+	}
+}
@@ -0,0 +1,664 @@
+
+use std::{
+	collections::{HashMap, HashSet},
+	fs, io,
+	path::Path,
+	sync::Arc,
+};
+use crate::{
+	diagnostic::Diagnostic,
+	encoding::{self, Decoder},
+	extract::{Columns, LineExtractor, Markup},
+	segmentation::{self, grapheme_count},
+};
+
+/// Number of suggestions given for an unknown word.
+const SUGGESTIONS: usize = 3;
+/// Most edits between an unknown word and a suggestion.
+const MAX_DISTANCE: usize = 2;
+
+/// How the flags of a Hunspell dictionary are written.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum FlagKind {
+	/// One character per flag.
+	#[default]
+	Char,
+	/// Two characters per flag.
+	Long,
+	/// Numbers separated by commas.
+	Number,
+}
+
+/// A character of an affix condition.
+enum CharClass {
+	Any,
+	OneOf(Vec<char>),
+	NoneOf(Vec<char>),
+}
+
+impl CharClass {
+	fn matches(&self, c: char) -> bool {
+		match self {
+			CharClass::Any => true,
+			CharClass::OneOf(chars) => chars.contains(&c),
+			CharClass::NoneOf(chars) => !chars.contains(&c),
+		}
+	}
+}
+
+/// A prefix or suffix rule of a Hunspell affix file.
+struct Affix {
+	flag: String,
+	prefix: bool,
+	/// Whether the rule combines with the rules on the other side.
+	cross: bool,
+	strip: String,
+	add: String,
+	condition: Vec<CharClass>,
+}
+
+fn parse_condition(condition: &str) -> Vec<CharClass> {
+	let mut classes = Vec::new();
+	let mut chars = condition.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			'.' => classes.push(CharClass::Any),
+			'[' => {
+				let mut set: Vec<char> = chars.by_ref().take_while(|c| *c != ']').collect();
+				if set.first() == Some(&'^') {
+					set.remove(0);
+					classes.push(CharClass::NoneOf(set));
+				} else {
+					classes.push(CharClass::OneOf(set));
+				}
+			}
+			c => classes.push(CharClass::OneOf(vec![c])),
+		}
+	}
+	classes
+}
+
+impl Affix {
+	/// Whether the rule applies to `word`, by its condition and the text it
+	/// strips.
+	fn accepts(&self, word: &str) -> bool {
+		let chars: Vec<char> = word.chars().collect();
+		if chars.len() < self.condition.len() {
+			return false;
+		}
+		let start = if self.prefix { 0 } else { chars.len() - self.condition.len() };
+		let matches = self.condition.iter().zip(&chars[start..]).all(|(class, c)| class.matches(*c));
+		matches && if self.prefix { word.starts_with(&self.strip) } else { word.ends_with(&self.strip) }
+	}
+
+	/// `word` with the affix, the stripped text removed.
+	fn attach(&self, word: &str) -> String {
+		if self.prefix {
+			format!("{}{}", self.add, word.strip_prefix(self.strip.as_str()).unwrap_or(word))
+		} else {
+			format!("{}{}", word.strip_suffix(self.strip.as_str()).unwrap_or(word), self.add)
+		}
+	}
+}
+
+/// The rules of a Hunspell affix file the dictionary words are inflected
+/// with. Only the flags, the prefixes and the suffixes are read: compounds
+/// and the other options are not supported.
+#[derive(Default)]
+struct Affixes {
+	kind: FlagKind,
+	rules: Vec<Affix>,
+	/// Flags of the entries which are not words on their own.
+	not_words: Vec<String>,
+}
+
+impl Affixes {
+	fn parse(content: &str) -> Affixes {
+		let mut affixes = Affixes::default();
+		// Whether the rules of each flag cross, from their header.
+		let mut headers: HashMap<(&str, &str), bool> = HashMap::new();
+		for line in content.lines() {
+			let fields: Vec<&str> = line.split_whitespace().collect();
+			match fields.as_slice() {
+				["FLAG", "long", ..] => affixes.kind = FlagKind::Long,
+				["FLAG", "num", ..] => affixes.kind = FlagKind::Number,
+				["NEEDAFFIX" | "ONLYINCOMPOUND" | "FORBIDDENWORD", flag, ..] => affixes.not_words.push(flag.to_string()),
+				[kind @ ("PFX" | "SFX"), flag, cross, count]
+					if !headers.contains_key(&(*kind, *flag)) && count.parse::<usize>().is_ok() =>
+				{
+					headers.insert((kind, flag), *cross == "Y");
+				}
+				[kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+					let none = |text: &str| if text == "0" { String::new() } else { text.to_string() };
+					affixes.rules.push(Affix {
+						flag: flag.to_string(),
+						prefix: *kind == "PFX",
+						cross: headers.get(&(*kind, *flag)).copied().unwrap_or(false),
+						strip: none(strip),
+						// Flags after a '/' continue the affix: they are left out.
+						add: none(add.split('/').next().unwrap_or("")),
+						condition: parse_condition(rest.first().unwrap_or(&".")),
+					});
+				}
+				_ => (),
+			}
+		}
+		affixes
+	}
+
+	fn flags(&self, flags: &str) -> Vec<String> {
+		match self.kind {
+			FlagKind::Char => flags.chars().map(String::from).collect(),
+			FlagKind::Long => {
+				let chars: Vec<char> = flags.chars().collect();
+				chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+			}
+			FlagKind::Number => flags.split(',').map(|flag| flag.trim().to_string()).collect(),
+		}
+	}
+
+	/// Every form of `word` made with the rules of its `flags`; a suffix and
+	/// a prefix which both cross are combined.
+	fn expand(&self, word: &str, flags: &[String]) -> Vec<String> {
+		let rules: Vec<&Affix> = self.rules.iter().filter(|rule| flags.contains(&rule.flag)).collect();
+		let mut forms = Vec::new();
+		let mut crossing = Vec::new();
+		for rule in rules.iter().filter(|rule| !rule.prefix && rule.accepts(word)) {
+			let form = rule.attach(word);
+			if rule.cross {
+				crossing.push(form.clone());
+			}
+			forms.push(form);
+		}
+		for rule in rules.iter().filter(|rule| rule.prefix && rule.accepts(word)) {
+			forms.push(rule.attach(word));
+			if rule.cross {
+				forms.extend(crossing.iter().map(|suffixed| rule.attach(suffixed)));
+			}
+		}
+		forms
+	}
+}
+
+/// Reads a text file in the encoding detected from its first bytes, like
+/// the analysed inputs.
+fn read_text(path: &Path) -> io::Result<String> {
+	let bytes = fs::read(path)?;
+	let (detected, bom) = encoding::detect(&bytes);
+	let mut decoder = Decoder::guessed(detected);
+	let mut text = String::new();
+	decoder.decode(&bytes[bom..], |piece| text.push_str(piece))?;
+	decoder.finish(|piece| text.push_str(piece))?;
+	Ok(text)
+}
+
+/// Whether the letters of `word` are capitals.
+fn is_upper(word: &str) -> bool {
+	word.chars().any(char::is_alphabetic) && !word.chars().any(char::is_lowercase)
+}
+
+/// `word` with a capital first letter and the rest in lower case.
+fn capitalise(word: &str) -> String {
+	let mut chars = word.chars();
+	match chars.next() {
+		Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+		None => String::new(),
+	}
+}
+
+/// Edit distance between `a` and `b`, a swap of two neighbouring letters
+/// counting as one edit, `None` when it is above `max`.
+fn distance(a: &[char], b: &[char], max: usize) -> Option<usize> {
+	if a.len().abs_diff(b.len()) > max {
+		return None;
+	}
+	let mut before: Vec<usize> = vec![0; b.len() + 1];
+	let mut previous: Vec<usize> = (0..=b.len()).collect();
+	let mut current: Vec<usize> = vec![0; b.len() + 1];
+	for i in 1..=a.len() {
+		current[0] = i;
+		let mut least = i;
+		for j in 1..=b.len() {
+			let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+			let mut edits = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+			if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+				edits = edits.min(before[j - 2] + 1);
+			}
+			current[j] = edits;
+			least = least.min(edits);
+		}
+		if least > max {
+			return None;
+		}
+		std::mem::swap(&mut before, &mut previous);
+		std::mem::swap(&mut previous, &mut current);
+	}
+	Some(previous[b.len()]).filter(|edits| *edits <= max)
+}
+
+/// The words known to the spelling check.
+#[derive(Clone, Default)]
+pub struct Dictionary {
+	words: HashSet<String>,
+	/// The words in lower case, by their number of characters, to look for
+	/// suggestions.
+	by_len: HashMap<usize, Vec<(Vec<char>, String)>>,
+}
+
+impl Dictionary {
+	pub fn new() -> Dictionary {
+		Dictionary::default()
+	}
+
+	pub fn from_file(path: &Path) -> io::Result<Dictionary> {
+		let mut dictionary = Dictionary::new();
+		dictionary.add_file(path)?;
+		Ok(dictionary)
+	}
+
+	/// Adds the words of a Hunspell dictionary when `path` ends with ".dic",
+	/// inflected with the affix file of the same name when there is one,
+	/// or else of a plain list with one or more words per line; everything
+	/// after a '#' is a comment.
+	pub fn add_file(&mut self, path: &Path) -> io::Result<()> {
+		let content = read_text(path)?;
+		if path.extension().is_some_and(|extension| extension == "dic") {
+			let affixes = match read_text(&path.with_extension("aff")) {
+				Ok(affixes) => Affixes::parse(&affixes),
+				Err(error) if error.kind() == io::ErrorKind::NotFound => Affixes::default(),
+				Err(error) => return Err(error),
+			};
+			self.add_hunspell(&content, &affixes);
+		} else {
+			for line in content.lines() {
+				self.add_list(line.split('#').next().unwrap_or(""));
+			}
+		}
+		Ok(())
+	}
+
+	fn add_hunspell(&mut self, content: &str, affixes: &Affixes) {
+		for (i, line) in content.lines().enumerate() {
+			// The first line is the number of words; a tab starts the
+			// description of the word.
+			let entry = line.split(['\t', ' ']).next().unwrap_or("");
+			if entry.is_empty() || (i == 0 && entry.parse::<usize>().is_ok()) {
+				continue;
+			}
+			let (word, flags) = match entry.split_once('/') {
+				Some((word, flags)) => (word, affixes.flags(flags)),
+				None => (entry, Vec::new()),
+			};
+			if !flags.iter().any(|flag| affixes.not_words.contains(flag)) {
+				self.add_word(word);
+			}
+			for form in affixes.expand(word, &flags) {
+				self.add_word(&form);
+			}
+		}
+	}
+
+	/// Adds the words of `list`, separated by whitespace.
+	pub fn add_list(&mut self, list: &str) {
+		for word in list.split_whitespace() {
+			self.add_word(word);
+		}
+	}
+
+	pub fn add_word(&mut self, word: &str) {
+		let word = word.replace('’', "'");
+		if word.is_empty() || self.words.contains(&word) {
+			return;
+		}
+		let lower: Vec<char> = word.to_lowercase().chars().collect();
+		self.by_len.entry(lower.len()).or_default().push((lower, word.clone()));
+		self.words.insert(word);
+	}
+
+	/// Whether `word` is known as it is written, or in lower case when it is
+	/// capitalised or in capitals, like at the start of a sentence. A
+	/// possessive "'s" is left out.
+	pub fn contains(&self, word: &str) -> bool {
+		let word = word.replace('’', "'");
+		if self.words.contains(&word) {
+			return true;
+		}
+		let capitalised = capitalise(&word);
+		if (word == capitalised || is_upper(&word)) && self.words.contains(&word.to_lowercase()) {
+			return true;
+		}
+		if is_upper(&word) && self.words.contains(&capitalised) {
+			return true;
+		}
+		match word.strip_suffix("'s").or(word.strip_suffix("'S")) {
+			Some(owner) => !owner.is_empty() && self.contains(owner),
+			None => false,
+		}
+	}
+
+	/// The known words at most two edits away from `word` (a letter added,
+	/// removed or replaced, or two letters swapped), the closest first, with
+	/// the capitals of `word`.
+	pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+		let lower: Vec<char> = word.to_lowercase().chars().collect();
+		let mut found: Vec<(usize, bool, &str)> = Vec::new();
+		for len in lower.len().saturating_sub(MAX_DISTANCE)..=lower.len() + MAX_DISTANCE {
+			for (known, original) in self.by_len.get(&len).into_iter().flatten() {
+				if let Some(edits) = distance(&lower, known, MAX_DISTANCE) {
+					// Words starting with another letter come after.
+					found.push((edits, known.first() != lower.first(), original));
+				}
+			}
+		}
+		found.sort();
+		let mut suggestions: Vec<String> = Vec::new();
+		for (_, _, original) in found {
+			let suggestion = if is_upper(word) && word.chars().count() > 1 {
+				original.to_uppercase()
+			} else if word == capitalise(word) {
+				capitalise(original)
+			} else {
+				original.to_string()
+			};
+			if !suggestions.contains(&suggestion) {
+				suggestions.push(suggestion);
+			}
+			if suggestions.len() == limit {
+				break;
+			}
+		}
+		suggestions
+	}
+
+	pub fn len(&self) -> usize {
+		self.words.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.words.is_empty()
+	}
+}
+
+/// A word missing from the dictionary, where it was found.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Misspelling {
+	/// Line number, from 1.
+	pub line: usize,
+	/// Column in user-perceived characters, from 1.
+	pub column: usize,
+	pub word: String,
+	/// The closest known words, the closest first.
+	pub suggestions: Vec<String>,
+}
+
+/// What the spelling check found in a text.
+#[derive(Clone, Default)]
+pub struct Spelling {
+	checked: usize,
+	unknown: usize,
+	misspellings: Vec<Misspelling>,
+}
+
+impl Spelling {
+	/// Adds the counts of `other`. Positions belong to a single text: the
+	/// list of misspellings is cleared.
+	pub fn merge(&mut self, other: &Spelling) {
+		self.checked += other.checked;
+		self.unknown += other.unknown;
+		self.misspellings.clear();
+	}
+
+	/// Number of words looked up in the dictionary.
+	pub fn checked(&self) -> usize {
+		self.checked
+	}
+	/// Number of words missing from the dictionary.
+	pub fn unknown(&self) -> usize {
+		self.unknown
+	}
+	/// The unknown words, in order.
+	pub fn misspellings(&self) -> &[Misspelling] {
+		&self.misspellings
+	}
+
+	pub fn diagnostics(&self) -> Vec<Diagnostic> {
+		self.misspellings
+			.iter()
+			.map(|misspelling| {
+				let quoted: Vec<String> = misspelling.suggestions.iter().map(|word| format!("'{}'", word)).collect();
+				let message = match quoted.split_last() {
+					None => format!("unknown word '{}'", misspelling.word),
+					Some((last, [])) => format!("unknown word '{}', did you mean {}?", misspelling.word, last),
+					Some((last, first)) => {
+						format!("unknown word '{}', did you mean {} or {}?", misspelling.word, first.join(", "), last)
+					}
+				};
+				Diagnostic::new(misspelling.line, misspelling.column, "spelling", message)
+			})
+			.collect()
+	}
+}
+
+/// Whether `word` is looked up: words with digits, underscores or capitals
+/// after a small letter, like the names of code, are not.
+fn is_checked(word: &str) -> bool {
+	let mut lower_seen = false;
+	for c in word.chars() {
+		if c.is_numeric() || c == '_' || (lower_seen && c.is_uppercase()) {
+			return false;
+		}
+		lower_seen |= c.is_lowercase();
+	}
+	grapheme_count(word) > 1
+}
+
+/// Looks up the words of the lines, keeping what it found.
+struct Checker {
+	dictionary: Arc<Dictionary>,
+	/// Suggestions of the unknown words already seen.
+	suggestions: HashMap<String, Vec<String>>,
+	spelling: Spelling,
+}
+
+impl Checker {
+	fn check_line(&mut self, number: usize, line: &str, extracted: &str) {
+		let mut columns = Columns::new(line);
+		for word in segmentation::words(extracted) {
+			let column = columns.find(word);
+			if !is_checked(word) {
+				continue;
+			}
+			self.spelling.checked += 1;
+			if self.dictionary.contains(word) {
+				continue;
+			}
+			let dictionary = &self.dictionary;
+			let suggestions = self
+				.suggestions
+				.entry(word.to_string())
+				.or_insert_with(|| dictionary.suggestions(word, SUGGESTIONS))
+				.clone();
+			self.spelling.unknown += 1;
+			self.spelling.misspellings.push(Misspelling { line: number, column, word: word.to_string(), suggestions });
+		}
+	}
+}
+
+/// Running spelling check of a text, line by line. The markup of every line
+/// is left out, and the unknown words are found back in the line as read to
+/// report their column.
+pub struct Speller {
+	lines: LineExtractor,
+	checker: Checker,
+}
+
+impl Speller {
+	pub fn new(dictionary: Arc<Dictionary>, markup: Markup) -> Speller {
+		let checker = Checker { dictionary, suggestions: HashMap::new(), spelling: Spelling::default() };
+		Speller { lines: LineExtractor::new(markup), checker }
+	}
+
+	/// Feeds the next piece of the text; a line can be cut between pieces.
+	pub fn push(&mut self, text: &str) {
+		let checker = &mut self.checker;
+		self.lines.push(text, |number, line, extracted| checker.check_line(number, line, extracted));
+	}
+
+	/// Checks the last line, even without a line break.
+	pub fn finish(mut self) -> Spelling {
+		let checker = &mut self.checker;
+		self.lines.finish(|number, line, extracted| checker.check_line(number, line, extracted));
+		self.checker.spelling
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{env, process};
+
+	const AFFIXES: &str = "SET UTF-8
+PFX A Y 1
+PFX A 0 re .
+
+SFX B Y 2
+SFX B 0 ed [^y]
+SFX B y ied [^aeiou]y
+
+SFX C N 1
+SFX C 0 s .
+
+PFX U N 1
+PFX U 0 un .
+
+NEEDAFFIX X
+";
+
+	const WORDS: &str = "6
+work/AB
+carry/AB
+play/BC
+do/UC
+fuzz/XB
+Paris\tpo:noun
+";
+
+	/// The dictionary of `WORDS` inflected with `AFFIXES`, read from files.
+	fn dictionary() -> Dictionary {
+		let root = env::temp_dir().join(format!("text_analyser_spelling_{}", process::id()));
+		fs::create_dir_all(&root).unwrap();
+		fs::write(root.join("test.aff"), AFFIXES).unwrap();
+		fs::write(root.join("test.dic"), WORDS).unwrap();
+		let dictionary = Dictionary::from_file(&root.join("test.dic"));
+		fs::remove_dir_all(&root).unwrap();
+		dictionary.unwrap()
+	}
+
+	fn chars(word: &str) -> Vec<char> {
+		word.chars().collect()
+	}
+
+	#[test]
+	fn affixes_cross() {
+		let dictionary = dictionary();
+		for word in ["work", "worked", "rework", "reworked", "carry", "carried", "recarry", "recarried"] {
+			assert!(dictionary.contains(word), "{}", word);
+		}
+		// Either side of a prefix and a suffix which do not both cross.
+		for word in ["do", "dos", "undo", "plays"] {
+			assert!(dictionary.contains(word), "{}", word);
+		}
+		assert!(!dictionary.contains("undos"));
+	}
+
+	#[test]
+	fn affix_strip_and_condition() {
+		let dictionary = dictionary();
+		// "y" is stripped after a consonant only, "ed" is added after
+		// anything but a "y".
+		assert!(dictionary.contains("carried"));
+		assert!(!dictionary.contains("carryed"));
+		assert!(!dictionary.contains("plaied"));
+		assert!(!dictionary.contains("played"));
+		// An entry which needs an affix is not a word on its own.
+		assert!(dictionary.contains("fuzzed"));
+		assert!(!dictionary.contains("fuzz"));
+		assert_eq!(dictionary.len(), 15);
+	}
+
+	#[test]
+	fn flag_kinds() {
+		let long = Affixes::parse("FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n");
+		assert_eq!(long.flags("AaBb"), ["Aa", "Bb"]);
+		assert_eq!(long.expand("cat", &long.flags("Aa")), ["cats"]);
+		let numbers = Affixes::parse("FLAG num\nSFX 12 Y 1\nSFX 12 0 s .\n");
+		assert_eq!(numbers.flags("3,12"), ["3", "12"]);
+		assert_eq!(numbers.expand("cat", &numbers.flags("3,12")), ["cats"]);
+	}
+
+	#[test]
+	fn distances() {
+		assert_eq!(distance(&chars("work"), &chars("work"), 2), Some(0));
+		assert_eq!(distance(&chars("wrok"), &chars("work"), 2), Some(1));
+		assert_eq!(distance(&chars("wor"), &chars("work"), 2), Some(1));
+		assert_eq!(distance(&chars("wirk"), &chars("work"), 2), Some(1));
+		assert_eq!(distance(&chars("owrd"), &chars("word"), 2), Some(1));
+		assert_eq!(distance(&chars(""), &chars("ab"), 2), Some(2));
+		assert_eq!(distance(&chars("kitten"), &chars("sitting"), 3), Some(3));
+		// Above the most edits, by length or by letters.
+		assert_eq!(distance(&chars("kitten"), &chars("sitting"), 2), None);
+		assert_eq!(distance(&chars("a"), &chars("abcd"), 2), None);
+		// A swapped pair is not edited again.
+		assert_eq!(distance(&chars("ca"), &chars("abc"), 3), Some(3));
+	}
+
+	#[test]
+	fn capitals() {
+		let mut dictionary = dictionary();
+		dictionary.add_word("NASA");
+		// A word in lower case can be capitalised or in capitals.
+		assert!(dictionary.contains("Work"));
+		assert!(dictionary.contains("WORK"));
+		assert!(!dictionary.contains("wOrk"));
+		// A capitalised word can be in capitals, not in lower case.
+		assert!(dictionary.contains("Paris"));
+		assert!(dictionary.contains("PARIS"));
+		assert!(!dictionary.contains("paris"));
+		// A word in capitals is kept as it is.
+		assert!(!dictionary.contains("Nasa"));
+		assert!(!dictionary.contains("nasa"));
+	}
+
+	#[test]
+	fn possessives() {
+		let dictionary = dictionary();
+		assert!(dictionary.contains("Paris's"));
+		assert!(dictionary.contains("Paris’s"));
+		assert!(dictionary.contains("WORK'S"));
+		assert!(!dictionary.contains("'s"));
+		assert!(!dictionary.contains("paris's"));
+	}
+
+	#[test]
+	fn suggestions() {
+		let mut dictionary = Dictionary::new();
+		dictionary.add_list("work word world ward");
+		assert_eq!(dictionary.suggestions("wrok", 2), ["work", "word"]);
+		assert_eq!(dictionary.suggestions("Wrok", 1), ["Work"]);
+		assert_eq!(dictionary.suggestions("WROK", 1), ["WORK"]);
+		assert!(dictionary.suggestions("xyzzy", 3).is_empty());
+	}
+
+	#[test]
+	fn positions() {
+		let mut speller = Speller::new(Arc::new(dictionary()), Markup::Markdown);
+		for piece in ["# Work\n\n`fuzz` *wrok* and ", "carryed PARIS\n"] {
+			speller.push(piece);
+		}
+		let spelling = speller.finish();
+		let found: Vec<(usize, usize, &str)> =
+			spelling.misspellings().iter().map(|found| (found.line, found.column, found.word.as_str())).collect();
+		assert_eq!(found, [(3, 9, "wrok"), (3, 15, "and"), (3, 19, "carryed")]);
+		assert_eq!((spelling.checked(), spelling.unknown()), (5, 3));
+	}
+}
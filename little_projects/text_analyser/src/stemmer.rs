@@ -0,0 +1,767 @@
+
+use std::str::FromStr;
+use crate::language::Language;
+
+/// How inflected forms of a word are grouped in the counts.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Reduction {
+	#[default]
+	None,
+	/// Snowball stems: "running" and "runs" are both counted as "run",
+	/// "connection" as "connect". Stems are not always words.
+	Stem,
+	/// Dictionary forms: irregular forms from a bundled list, and regular
+	/// plurals and verb endings removed, so "went" counts as "go" and
+	/// "cities" as "city".
+	Lemma,
+}
+
+impl FromStr for Reduction {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().as_str() {
+			"none" => Ok(Reduction::None),
+			"stem" => Ok(Reduction::Stem),
+			"lemma" => Ok(Reduction::Lemma),
+			_ => Err(format!("unknown reduction '{}' (expected none, stem or lemma)", s)),
+		}
+	}
+}
+
+/// Reduces `word` to the form counted for it. Only English and French
+/// have rules; the words of other languages are kept as they are. Reduced
+/// words are lowercased.
+pub fn reduce(word: &str, reduction: Reduction, language: Language) -> String {
+	match (reduction, language) {
+		(Reduction::None, _) | (_, Language::Spanish | Language::German) => word.to_string(),
+		(Reduction::Stem, Language::English) => english_stem(&word.to_lowercase()),
+		(Reduction::Stem, Language::French) => french_stem(&word.to_lowercase()),
+		(Reduction::Lemma, Language::English) => english_lemma(&word.to_lowercase()),
+		(Reduction::Lemma, Language::French) => french_lemma(&word.to_lowercase()),
+	}
+}
+
+/// A word being stemmed, with the start of its regions.
+struct Word {
+	chars: Vec<char>,
+	rv: usize,
+	r1: usize,
+	r2: usize,
+}
+
+impl Word {
+	fn len(&self) -> usize {
+		self.chars.len()
+	}
+
+	fn ends(&self, suffix: &str) -> bool {
+		let suffix: Vec<char> = suffix.chars().collect();
+		self.chars.ends_with(&suffix)
+	}
+
+	/// Index where `suffix` starts, when the word ends with it.
+	fn start(&self, suffix: &str) -> usize {
+		self.len() - suffix.chars().count()
+	}
+
+	/// The longest of `suffixes` the word ends with.
+	fn longest<'a>(&self, suffixes: &[&'a str]) -> Option<&'a str> {
+		suffixes
+			.iter()
+			.filter(|suffix| self.ends(suffix))
+			.max_by_key(|suffix| suffix.chars().count())
+			.copied()
+	}
+
+	fn in_rv(&self, suffix: &str) -> bool {
+		self.start(suffix) >= self.rv
+	}
+
+	fn in_r1(&self, suffix: &str) -> bool {
+		self.start(suffix) >= self.r1
+	}
+
+	fn in_r2(&self, suffix: &str) -> bool {
+		self.start(suffix) >= self.r2
+	}
+
+	fn replace(&mut self, suffix: &str, with: &str) {
+		let start = self.start(suffix);
+		self.chars.truncate(start);
+		self.chars.extend(with.chars());
+	}
+
+	fn remove(&mut self, suffix: &str) {
+		self.replace(suffix, "");
+	}
+
+	/// The character before `suffix`.
+	fn before(&self, suffix: &str) -> Option<char> {
+		self.start(suffix).checked_sub(1).map(|i| self.chars[i])
+	}
+}
+
+impl std::fmt::Display for Word {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let text: String = self.chars.iter().collect();
+		write!(f, "{}", text)
+	}
+}
+
+/// The region after the first non-vowel following a vowel, from `from`.
+fn region(chars: &[char], from: usize, is_vowel: fn(char) -> bool) -> usize {
+	(from + 1..chars.len())
+		.find(|i| !is_vowel(chars[*i]) && is_vowel(chars[*i - 1]))
+		.map(|i| i + 1)
+		.unwrap_or(chars.len())
+}
+
+fn english_vowel(c: char) -> bool {
+	matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+}
+
+/// Whether the syllable ending at `end` (excluded) is short.
+fn english_short_syllable(chars: &[char], end: usize) -> bool {
+	match end {
+		2 => english_vowel(chars[0]) && !english_vowel(chars[1]),
+		end if end >= 3 => {
+			let (a, b, c) = (chars[end - 3], chars[end - 2], chars[end - 1]);
+			!english_vowel(a) && english_vowel(b) && !english_vowel(c) && !matches!(c, 'w' | 'x' | 'Y')
+		}
+		_ => false,
+	}
+}
+
+const ENGLISH_EXCEPTIONS: &[(&str, &str)] = &[
+	("skis", "ski"), ("skies", "sky"), ("dying", "die"), ("lying", "lie"), ("tying", "tie"), ("idly", "idl"),
+	("gently", "gentl"), ("ugly", "ugli"), ("early", "earli"), ("only", "onli"), ("singly", "singl"),
+	("sky", "sky"), ("news", "news"), ("howe", "howe"), ("atlas", "atlas"), ("cosmos", "cosmos"),
+	("bias", "bias"), ("andes", "andes"),
+];
+
+const ENGLISH_INVARIANT: &[&str] = &["inning", "outing", "canning", "herring", "earring", "proceed", "exceed", "succeed"];
+
+/// The English (Porter2) Snowball stemmer.
+pub fn english_stem(word: &str) -> String {
+	let word = word.trim_start_matches('\'');
+	if word.chars().count() <= 2 {
+		return word.to_string();
+	}
+	if let Some((_, stem)) = ENGLISH_EXCEPTIONS.iter().find(|(form, _)| *form == word) {
+		return stem.to_string();
+	}
+
+	let mut chars: Vec<char> = word.chars().collect();
+	for i in 0..chars.len() {
+		if chars[i] == 'y' && (i == 0 || english_vowel(chars[i - 1])) {
+			chars[i] = 'Y';
+		}
+	}
+	let r1 = ["gener", "commun", "arsen"]
+		.iter()
+		.find(|prefix| word.starts_with(*prefix))
+		.map(|prefix| prefix.len())
+		.unwrap_or_else(|| region(&chars, 0, english_vowel));
+	let r2 = region(&chars, r1, english_vowel);
+	let mut w = Word { chars, rv: 0, r1, r2 };
+
+	// Step 0: possessives.
+	if let Some(suffix) = w.longest(&["'s'", "'s", "'"]) {
+		w.remove(suffix);
+	}
+
+	// Step 1a: plurals.
+	match w.longest(&["sses", "ied", "ies", "us", "ss", "s"]) {
+		Some("sses") => w.replace("sses", "ss"),
+		Some(suffix @ ("ied" | "ies")) => {
+			let with = if w.start(suffix) > 1 { "i" } else { "ie" };
+			w.replace(suffix, with);
+		}
+		Some("s") => {
+			let stem = &w.chars[..w.len() - 1];
+			if stem.len() >= 2 && stem[..stem.len() - 1].iter().any(|c| english_vowel(*c)) {
+				w.remove("s");
+			}
+		}
+		_ => (),
+	}
+	if ENGLISH_INVARIANT.contains(&w.to_string().as_str()) {
+		return w.to_string();
+	}
+
+	// Step 1b: past tenses and gerunds.
+	match w.longest(&["eed", "eedly", "ed", "edly", "ing", "ingly"]) {
+		Some(suffix @ ("eed" | "eedly")) if w.in_r1(suffix) => {
+			w.replace(suffix, "ee");
+		}
+		Some("eed" | "eedly") => (),
+		Some(suffix) => {
+			let start = w.start(suffix);
+			if w.chars[..start].iter().any(|c| english_vowel(*c)) {
+				w.remove(suffix);
+				if w.ends("at") || w.ends("bl") || w.ends("iz") {
+					w.chars.push('e');
+				} else if ["bb", "dd", "ff", "gg", "mm", "nn", "pp", "rr", "tt"].iter().any(|double| w.ends(double)) {
+					w.chars.pop();
+				} else if w.r1 >= w.len() && english_short_syllable(&w.chars, w.len()) {
+					w.chars.push('e');
+				}
+			}
+		}
+		None => (),
+	}
+
+	// Step 1c: final y.
+	let len = w.len();
+	if len > 2 && matches!(w.chars[len - 1], 'y' | 'Y') && !english_vowel(w.chars[len - 2]) {
+		w.chars[len - 1] = 'i';
+	}
+
+	// Step 2.
+	const STEP2: &[(&str, &str)] = &[
+		("tional", "tion"), ("enci", "ence"), ("anci", "ance"), ("abli", "able"), ("entli", "ent"),
+		("izer", "ize"), ("ization", "ize"), ("ational", "ate"), ("ation", "ate"), ("ator", "ate"),
+		("alism", "al"), ("aliti", "al"), ("alli", "al"), ("fulness", "ful"), ("ousli", "ous"),
+		("ousness", "ous"), ("iveness", "ive"), ("iviti", "ive"), ("biliti", "ble"), ("bli", "ble"),
+		("ogi", "og"), ("fulli", "ful"), ("lessli", "less"), ("li", ""),
+	];
+	let suffixes: Vec<&str> = STEP2.iter().map(|(suffix, _)| *suffix).collect();
+	if let Some(suffix) = w.longest(&suffixes) {
+		if w.in_r1(suffix) {
+			let with = STEP2.iter().find(|(from, _)| *from == suffix).map(|(_, with)| *with).unwrap_or("");
+			match suffix {
+				"ogi" if w.before(suffix) != Some('l') => (),
+				"li" if !matches!(w.before(suffix), Some('c' | 'd' | 'e' | 'g' | 'h' | 'k' | 'm' | 'n' | 'r' | 't')) => (),
+				_ => w.replace(suffix, with),
+			}
+		}
+	}
+
+	// Step 3.
+	const STEP3: &[(&str, &str)] = &[
+		("tional", "tion"), ("ational", "ate"), ("alize", "al"), ("icate", "ic"), ("iciti", "ic"),
+		("ical", "ic"), ("ful", ""), ("ness", ""), ("ative", ""),
+	];
+	let suffixes: Vec<&str> = STEP3.iter().map(|(suffix, _)| *suffix).collect();
+	if let Some(suffix) = w.longest(&suffixes) {
+		if w.in_r1(suffix) && (suffix != "ative" || w.in_r2(suffix)) {
+			let with = STEP3.iter().find(|(from, _)| *from == suffix).map(|(_, with)| *with).unwrap_or("");
+			w.replace(suffix, with);
+		}
+	}
+
+	// Step 4.
+	let step4 = [
+		"al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ism", "ate", "iti", "ous",
+		"ive", "ize", "ion",
+	];
+	if let Some(suffix) = w.longest(&step4) {
+		if w.in_r2(suffix) && (suffix != "ion" || matches!(w.before(suffix), Some('s' | 't'))) {
+			w.remove(suffix);
+		}
+	}
+
+	// Step 5.
+	if w.ends("e") {
+		let len = w.len();
+		if w.in_r2("e") || (w.in_r1("e") && !english_short_syllable(&w.chars, len - 1)) {
+			w.remove("e");
+		}
+	} else if w.ends("l") && w.in_r2("l") && w.before("l") == Some('l') {
+		w.remove("l");
+	}
+
+	w.to_string().replace('Y', "y")
+}
+
+fn french_vowel(c: char) -> bool {
+	matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'â' | 'à' | 'ë' | 'é' | 'ê' | 'è' | 'ï' | 'î' | 'ô' | 'û' | 'ù')
+}
+
+/// Removes the verb endings of step 2 of the French stemmer, returns
+/// whether one was removed.
+fn french_verb_suffix(w: &mut Word) -> bool {
+	let step2a = [
+		"îmes", "ît", "îtes", "i", "ie", "ies", "ir", "ira", "irai", "iraIent", "irais", "irait", "iras", "irent",
+		"irez", "iriez", "irions", "irons", "iront", "is", "issaIent", "issais", "issait", "issant", "issante",
+		"issantes", "issants", "isse", "issent", "isses", "issez", "issiez", "issions", "issons", "it",
+	];
+	if let Some(suffix) = w.longest(&step2a) {
+		let before = w.start(suffix);
+		if w.in_rv(suffix) && before > w.rv && !french_vowel(w.chars[before - 1]) {
+			w.remove(suffix);
+			return true;
+		}
+	}
+
+	let step2b = [
+		"ions", "é", "ée", "ées", "és", "èrent", "er", "era", "erai", "eraIent", "erais", "erait", "eras", "erez",
+		"eriez", "erions", "erons", "eront", "ez", "iez", "âmes", "ât", "âtes", "a", "ai", "aIent", "ais", "ait",
+		"ant", "ante", "antes", "ants", "as", "asse", "assent", "asses", "assiez", "assions",
+	];
+	match w.longest(&step2b) {
+		Some("ions") if w.in_rv("ions") && w.in_r2("ions") => {
+			w.remove("ions");
+			return true;
+		}
+		Some("ions") => (),
+		Some(suffix @ ("âmes" | "ât" | "âtes" | "a" | "ai" | "aIent" | "ais" | "ait" | "ant" | "ante" | "antes"
+		| "ants" | "as" | "asse" | "assent" | "asses" | "assiez" | "assions")) if w.in_rv(suffix) => {
+			w.remove(suffix);
+			if w.ends("e") && w.in_rv("e") {
+				w.remove("e");
+			}
+			return true;
+		}
+		Some(suffix) if w.in_rv(suffix) => {
+			w.remove(suffix);
+			return true;
+		}
+		_ => (),
+	}
+	false
+}
+
+/// The French Snowball stemmer.
+pub fn french_stem(word: &str) -> String {
+	let mut chars: Vec<char> = word.chars().collect();
+	if chars.len() <= 2 {
+		return word.to_string();
+	}
+
+	// Vowels used as consonants are put in upper case.
+	for i in 0..chars.len() {
+		let before = i.checked_sub(1).map(|i| chars[i]);
+		let after = chars.get(i + 1).copied();
+		let between_vowels = before.is_some_and(french_vowel) && after.is_some_and(french_vowel);
+		match chars[i] {
+			'u' if between_vowels || before == Some('q') => chars[i] = 'U',
+			'i' if between_vowels => chars[i] = 'I',
+			'y' if before.is_some_and(french_vowel) || after.is_some_and(french_vowel) => chars[i] = 'Y',
+			_ => (),
+		}
+	}
+
+	let text: String = chars.iter().collect();
+	let rv = if ["par", "col", "tap"].iter().any(|prefix| text.starts_with(prefix))
+		|| (french_vowel(chars[0]) && french_vowel(chars[1]))
+	{
+		3
+	} else {
+		(1..chars.len()).find(|i| french_vowel(chars[*i])).map(|i| i + 1).unwrap_or(chars.len())
+	};
+	let r1 = region(&chars, 0, french_vowel);
+	let r2 = region(&chars, r1, french_vowel);
+	let mut w = Word { chars, rv, r1, r2 };
+	let original = w.chars.clone();
+
+	// Step 1: standard suffixes.
+	let step1 = [
+		"ance", "iqUe", "isme", "able", "iste", "eux", "ances", "iqUes", "ismes", "ables", "istes", "atrice", "ateur",
+		"ation", "atrices", "ateurs", "ations", "logie", "logies", "usion", "ution", "usions", "utions", "ence",
+		"ences", "ement", "ements", "ité", "ités", "if", "ive", "ifs", "ives", "eaux", "aux", "euse", "euses",
+		"issement", "issements", "amment", "emment", "ment", "ments",
+	];
+	let mut removed = false;
+	let mut verb = false;
+	match w.longest(&step1) {
+		Some(suffix @ ("ance" | "iqUe" | "isme" | "able" | "iste" | "eux" | "ances" | "iqUes" | "ismes" | "ables"
+		| "istes")) if w.in_r2(suffix) => {
+			w.remove(suffix);
+			removed = true;
+		}
+		Some(suffix @ ("atrice" | "ateur" | "ation" | "atrices" | "ateurs" | "ations")) if w.in_r2(suffix) => {
+			w.remove(suffix);
+			removed = true;
+			if w.ends("ic") {
+				if w.in_r2("ic") {
+					w.remove("ic");
+				} else {
+					w.replace("ic", "iqU");
+				}
+			}
+		}
+		Some(suffix @ ("logie" | "logies")) if w.in_r2(suffix) => {
+			w.replace(suffix, "log");
+			removed = true;
+		}
+		Some(suffix @ ("usion" | "ution" | "usions" | "utions")) if w.in_r2(suffix) => {
+			w.replace(suffix, "u");
+			removed = true;
+		}
+		Some(suffix @ ("ence" | "ences")) if w.in_r2(suffix) => {
+			w.replace(suffix, "ent");
+			removed = true;
+		}
+		Some(suffix @ ("ement" | "ements")) if w.in_rv(suffix) => {
+			w.remove(suffix);
+			removed = true;
+			if w.ends("iv") && w.in_r2("iv") {
+				w.remove("iv");
+				if w.ends("at") && w.in_r2("at") {
+					w.remove("at");
+				}
+			} else if w.ends("eus") {
+				if w.in_r2("eus") {
+					w.remove("eus");
+				} else if w.in_r1("eus") {
+					w.replace("eus", "eux");
+				}
+			} else if (w.ends("abl") && w.in_r2("abl")) || (w.ends("iqU") && w.in_r2("iqU")) {
+				let suffix = if w.ends("abl") { "abl" } else { "iqU" };
+				w.remove(suffix);
+			} else if (w.ends("ièr") && w.in_rv("ièr")) || (w.ends("Ièr") && w.in_rv("Ièr")) {
+				let suffix = if w.ends("ièr") { "ièr" } else { "Ièr" };
+				w.replace(suffix, "i");
+			}
+		}
+		Some(suffix @ ("ité" | "ités")) if w.in_r2(suffix) => {
+			w.remove(suffix);
+			removed = true;
+			if w.ends("abil") {
+				if w.in_r2("abil") {
+					w.remove("abil");
+				} else {
+					w.replace("abil", "abl");
+				}
+			} else if w.ends("ic") {
+				if w.in_r2("ic") {
+					w.remove("ic");
+				} else {
+					w.replace("ic", "iqU");
+				}
+			} else if w.ends("iv") && w.in_r2("iv") {
+				w.remove("iv");
+			}
+		}
+		Some(suffix @ ("if" | "ive" | "ifs" | "ives")) if w.in_r2(suffix) => {
+			w.remove(suffix);
+			removed = true;
+			if w.ends("at") && w.in_r2("at") {
+				w.remove("at");
+				if w.ends("ic") {
+					if w.in_r2("ic") {
+						w.remove("ic");
+					} else {
+						w.replace("ic", "iqU");
+					}
+				}
+			}
+		}
+		Some("eaux") => {
+			w.replace("eaux", "eau");
+			removed = true;
+		}
+		Some("aux") if w.in_r1("aux") => {
+			w.replace("aux", "al");
+			removed = true;
+		}
+		Some(suffix @ ("euse" | "euses")) => {
+			if w.in_r2(suffix) {
+				w.remove(suffix);
+				removed = true;
+			} else if w.in_r1(suffix) {
+				w.replace(suffix, "eux");
+				removed = true;
+			}
+		}
+		Some(suffix @ ("issement" | "issements")) if w.in_r1(suffix) && w.before(suffix).is_some_and(|c| !french_vowel(c)) => {
+			w.remove(suffix);
+			removed = true;
+		}
+		Some("amment") => {
+			verb = true;
+			if w.in_rv("amment") {
+				w.replace("amment", "ant");
+				removed = true;
+			}
+		}
+		Some("emment") => {
+			verb = true;
+			if w.in_rv("emment") {
+				w.replace("emment", "ent");
+				removed = true;
+			}
+		}
+		Some(suffix @ ("ment" | "ments")) => {
+			verb = true;
+			let start = w.start(suffix);
+			if w.in_rv(suffix) && start > w.rv && french_vowel(w.chars[start - 1]) {
+				w.remove(suffix);
+				removed = true;
+			}
+		}
+		_ => (),
+	}
+
+	// Step 2: verb suffixes, when step 1 removed nothing but adverbs.
+	if !removed || verb {
+		removed = french_verb_suffix(&mut w) || removed;
+	}
+
+	if removed || w.chars != original {
+		// Step 3.
+		match w.chars.last() {
+			Some('Y') => w.replace("Y", "i"),
+			Some('ç') => w.replace("ç", "c"),
+			_ => (),
+		}
+	} else {
+		// Step 4: residual suffixes.
+		if w.ends("s") && !matches!(w.before("s"), Some('a' | 'i' | 'o' | 'u' | 'è' | 's')) {
+			w.remove("s");
+		}
+		match w.longest(&["ion", "ier", "ière", "Ier", "Ière", "e", "ë"]) {
+			Some("ion") if w.in_rv("ion") && w.in_r2("ion") && matches!(w.before("ion"), Some('s' | 't')) => {
+				w.remove("ion");
+			}
+			Some(suffix @ ("ier" | "ière" | "Ier" | "Ière")) if w.in_rv(suffix) => {
+				w.replace(suffix, "i");
+			}
+			Some("e") if w.in_rv("e") => {
+				w.remove("e");
+			}
+			Some("ë") if w.in_rv("ë") && w.ends("guë") => {
+				w.remove("ë");
+			}
+			_ => (),
+		}
+	}
+
+	// Step 5: undouble.
+	if ["enn", "onn", "ett", "ell", "eill"].iter().any(|ending| w.ends(ending)) {
+		w.chars.pop();
+	}
+
+	// Step 6: unaccent.
+	let trailing = w.chars.iter().rev().take_while(|c| !french_vowel(**c)).count();
+	if trailing > 0 && trailing < w.len() {
+		let i = w.len() - trailing - 1;
+		if matches!(w.chars[i], 'é' | 'è') {
+			w.chars[i] = 'e';
+		}
+	}
+
+	w.to_string().replace('I', "i").replace('U', "u").replace('Y', "y")
+}
+
+const ENGLISH_IRREGULAR: &str = "
+am:be are:be is:be was:be were:be been:be being:be has:have had:have having:have does:do did:do done:do
+went:go gone:go goes:go said:say made:make took:take taken:take came:come saw:see seen:see knew:know
+known:know got:get gotten:get gave:give given:give found:find thought:think told:tell became:become
+left:leave felt:feel brought:bring began:begin begun:begin kept:keep held:hold wrote:write written:write
+stood:stand heard:hear meant:mean met:meet ran:run paid:pay sat:sit spoke:speak spoken:speak led:lead
+grew:grow grown:grow lost:lose fell:fall fallen:fall sent:send built:build understood:understand
+drew:draw drawn:draw broke:break broken:break spent:spend rose:rise risen:rise drove:drive driven:drive
+bought:buy wore:wear worn:wear chose:choose chosen:choose ate:eat eaten:eat sang:sing sung:sing
+men:man women:woman children:child people:person feet:foot teeth:tooth mice:mouse geese:goose
+better:good best:good worse:bad worst:bad
+";
+
+const FRENCH_IRREGULAR: &str = "
+suis:être es:être est:être sommes:être êtes:être sont:être étais:être était:être étions:être étiez:être
+étaient:être été:être serai:être sera:être seront:être fut:être soit:être soient:être
+ai:avoir as:avoir a:avoir avons:avoir avez:avoir ont:avoir avais:avoir avait:avoir avions:avoir
+aviez:avoir avaient:avoir eu:avoir aura:avoir auront:avoir aurait:avoir
+vais:aller vas:aller va:aller allons:aller allez:aller vont:aller allé:aller allée:aller irai:aller ira:aller
+fais:faire fait:faire faisons:faire faites:faire font:faire faisait:faire fera:faire
+peux:pouvoir peut:pouvoir pouvons:pouvoir pouvez:pouvoir peuvent:pouvoir pu:pouvoir pourra:pouvoir
+veux:vouloir veut:vouloir voulons:vouloir voulez:vouloir veulent:vouloir voulu:vouloir
+dois:devoir doit:devoir devons:devoir devez:devoir doivent:devoir dû:devoir
+sais:savoir sait:savoir savons:savoir savez:savoir savent:savoir su:savoir
+dit:dire dites:dire disent:dire vu:voir voit:voir voient:voir pris:prendre prend:prendre prennent:prendre
+yeux:œil
+";
+
+fn irregular(list: &str, word: &str) -> Option<String> {
+	list.split_whitespace()
+		.filter_map(|pair| pair.split_once(':'))
+		.find(|(form, _)| *form == word)
+		.map(|(_, lemma)| lemma.to_string())
+}
+
+/// Whether `word` ends with a consonant, a vowel and a consonant, and has a
+/// single vowel group: the verbs that take back an 'e' ("hop" from
+/// "hoping" is "hope").
+fn english_needs_e(word: &[char]) -> bool {
+	let groups = word.windows(2).filter(|pair| english_vowel(pair[1]) && !english_vowel(pair[0])).count()
+		+ usize::from(word.first().is_some_and(|c| english_vowel(*c)));
+	groups == 1 && english_short_syllable(word, word.len())
+}
+
+/// The dictionary form of an English word.
+pub fn english_lemma(word: &str) -> String {
+	if let Some(lemma) = irregular(ENGLISH_IRREGULAR, word) {
+		return lemma;
+	}
+	let chars: Vec<char> = word.chars().collect();
+	let len = chars.len();
+	if len <= 3 || word.ends_with("ss") || word.ends_with("us") || word.ends_with("is") {
+		return word.to_string();
+	}
+
+	if let Some(stem) = word.strip_suffix("ies") {
+		return if stem.chars().count() > 1 { format!("{}y", stem) } else { format!("{}ie", stem) };
+	}
+	for ending in ["sses", "shes", "ches", "xes", "zes"] {
+		if word.ends_with(ending) {
+			return word[..word.len() - 2].to_string();
+		}
+	}
+	if let Some(stem) = word.strip_suffix('s') {
+		return stem.to_string();
+	}
+
+	if let Some(stem) = word.strip_suffix("eed") {
+		if stem.chars().any(english_vowel) && !ENGLISH_INVARIANT.contains(&word) {
+			return format!("{}ee", stem);
+		}
+		return word.to_string();
+	}
+	for ending in ["ing", "ed"] {
+		let Some(stem) = word.strip_suffix(ending) else {
+			continue;
+		};
+		let stem_chars: Vec<char> = stem.chars().collect();
+		if stem_chars.len() < 2 || !stem_chars.iter().any(|c| english_vowel(*c)) {
+			return word.to_string();
+		}
+		if ending == "ed" && stem.ends_with('i') {
+			return format!("{}y", &stem[..stem.len() - 1]);
+		}
+		let n = stem_chars.len();
+		if n >= 2 && stem_chars[n - 1] == stem_chars[n - 2] && !matches!(stem_chars[n - 1], 'l' | 's' | 'z' | 'e') {
+			return stem_chars[..n - 1].iter().collect();
+		}
+		if english_needs_e(&stem_chars) {
+			return format!("{}e", stem);
+		}
+		return stem.to_string();
+	}
+	word.to_string()
+}
+
+/// The dictionary form of a French word: irregular verbs from the bundled
+/// list, plurals otherwise.
+pub fn french_lemma(word: &str) -> String {
+	if let Some(lemma) = irregular(FRENCH_IRREGULAR, word) {
+		return lemma;
+	}
+	if word.chars().count() <= 3 {
+		return word.to_string();
+	}
+	if let Some(stem) = word.strip_suffix("eaux") {
+		return format!("{}eau", stem);
+	}
+	if let Some(stem) = word.strip_suffix("aux") {
+		return format!("{}al", stem);
+	}
+	match word.strip_suffix(['s', 'x']) {
+		Some(stem) if !stem.ends_with(['s', 'x', 'z']) => stem.to_string(),
+		_ => word.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_stems(stem: fn(&str) -> String, cases: &[(&str, &str)]) {
+		for (word, expected) in cases {
+			assert_eq!(stem(word), *expected, "stem of '{}'", word);
+		}
+	}
+
+	#[test]
+	fn english_special_words() {
+		assert_stems(english_stem, &[
+			("skis", "ski"), ("skies", "sky"), ("dying", "die"), ("lying", "lie"), ("idly", "idl"),
+			("gently", "gentl"), ("ugly", "ugli"), ("early", "earli"), ("only", "onli"), ("singly", "singl"),
+			("news", "news"), ("atlas", "atlas"), ("cosmos", "cosmos"), ("andes", "andes"), ("howe", "howe"),
+			("inning", "inning"), ("outing", "outing"), ("canning", "canning"), ("proceed", "proceed"),
+			("exceed", "exceed"), ("succeed", "succeed"),
+		]);
+	}
+
+	#[test]
+	fn english_step_1() {
+		assert_stems(english_stem, &[
+			("caresses", "caress"), ("ponies", "poni"), ("ties", "tie"), ("cats", "cat"), ("feed", "feed"),
+			("agreed", "agre"), ("plastered", "plaster"), ("bled", "bled"), ("motoring", "motor"), ("sing", "sing"),
+			("conflated", "conflat"), ("troubled", "troubl"), ("sized", "size"), ("hopping", "hop"),
+			("tanned", "tan"), ("falling", "fall"), ("hissing", "hiss"), ("fizzed", "fizz"), ("failing", "fail"),
+			("filing", "file"), ("happy", "happi"), ("sky", "sky"), ("knives", "knive"),
+		]);
+	}
+
+	#[test]
+	fn english_steps_2_to_5() {
+		assert_stems(english_stem, &[
+			("generously", "generous"), ("relational", "relat"), ("conditional", "condit"),
+			("rational", "ration"), ("digitizer", "digit"), ("operator", "oper"), ("feudalism", "feudal"),
+			("decisiveness", "decis"), ("hopefulness", "hope"), ("callousness", "callous"),
+			("formalize", "formal"), ("electrical", "electr"), ("hopeful", "hope"), ("goodness", "good"),
+			("consolatory", "consolatori"), ("conspiracy", "conspiraci"), ("knightly", "knight"),
+			("consistently", "consist"), ("consolingly", "consol"), ("revival", "reviv"),
+			("allowance", "allow"), ("inference", "infer"), ("airliner", "airlin"), ("adjustable", "adjust"),
+			("defensible", "defens"), ("irritant", "irrit"), ("replacement", "replac"),
+			("dependent", "depend"), ("adoption", "adopt"), ("connection", "connect"), ("activate", "activ"),
+			("homologous", "homolog"), ("effective", "effect"), ("bowdlerize", "bowdler"),
+			("probate", "probat"), ("rate", "rate"), ("cease", "ceas"), ("controll", "control"), ("roll", "roll"),
+		]);
+	}
+
+	#[test]
+	fn english_prefix_regions() {
+		assert_stems(english_stem, &[("communism", "communism"), ("arsenal", "arsenal"), ("generate", "generat")]);
+	}
+
+	#[test]
+	fn french_step_1() {
+		assert_stems(french_stem, &[
+			("majestueusement", "majestu"), ("majestueuse", "majestu"), ("majestueux", "majestu"),
+			("majesté", "majest"), ("continuellement", "continuel"), ("continuité", "continu"),
+			("heureusement", "heureux"), ("évidemment", "évident"), ("absolument", "absolu"),
+			("importance", "import"), ("chevaux", "cheval"), ("animaux", "animal"), ("tranquillement", "tranquill"),
+		]);
+	}
+
+	#[test]
+	fn french_verb_suffixes() {
+		assert_stems(french_stem, &[
+			("finissons", "fin"), ("finissaient", "fin"), ("dormir", "dorm"), ("dormiront", "dorm"),
+			("continuer", "continu"), ("abandonnées", "abandon"), ("abandonneront", "abandon"),
+			("abandonnât", "abandon"), ("mangeaient", "mang"), ("aimée", "aim"), ("couramment", "cour"),
+			("voyageait", "voyag"), ("crédit", "cred"),
+		]);
+	}
+
+	#[test]
+	fn french_residual_suffixes() {
+		assert_stems(french_stem, &[
+			("mangeons", "mangeon"), ("nationales", "national"), ("tissus", "tissus"), ("paysannes", "paysann"),
+			("passions", "passion"),
+		]);
+	}
+
+	#[test]
+	fn lemmas() {
+		assert_stems(english_lemma, &[
+			("went", "go"), ("children", "child"), ("mice", "mouse"), ("better", "good"), ("was", "be"),
+			("feet", "foot"), ("running", "run"), ("stopped", "stop"), ("hoped", "hope"), ("making", "make"),
+			("studies", "study"), ("studied", "study"), ("flies", "fly"), ("cats", "cat"), ("glass", "glass"),
+		]);
+		assert_stems(french_lemma, &[
+			("yeux", "œil"), ("allons", "aller"), ("était", "être"), ("journaux", "journal"),
+			("chapeaux", "chapeau"), ("petites", "petite"), ("nez", "nez"),
+		]);
+	}
+
+	#[test]
+	fn other_languages_are_kept() {
+		assert_eq!(reduce("Corriendo", Reduction::Stem, Language::Spanish), "Corriendo");
+		assert_eq!(reduce("Running", Reduction::Stem, Language::English), "run");
+		assert_eq!(reduce("Running", Reduction::None, Language::English), "Running");
+	}
+}
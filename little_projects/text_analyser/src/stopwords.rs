@@ -0,0 +1,120 @@
+
+use std::{collections::HashSet, fs::read_to_string, io, path::Path};
+use crate::language::Language;
+
+const ENGLISH: &str = "
+a about above after again against all am an and any are as at be because been
+before being below between both but by can could did do does doing down during
+each few for from further had has have having he her here hers herself him
+himself his how i if in into is it its itself just me more most my myself no nor
+not now of off on once only or other our ours ourselves out over own same she
+should so some such than that the their theirs them themselves then there these
+they this those through to too under until up very was we were what when where
+which while who whom why will with would you your yours yourself yourselves
+";
+
+const FRENCH: &str = "
+a à ai aie aient aies ait alors as au aucun aura aurai auraient aurais aurait
+aux avaient avais avait avec avez aviez avions avoir avons ayant c ça ce ceci
+cela celle celles celui ces cet cette ceux chez comme d dans de des donc dont du
+elle elles en es est et étaient étais était été êtes étions être eu eux fait
+furent fut ici il ils j je l la le les leur leurs lui m ma mais me même mes moi
+mon n ne ni nos notre nous on ont ou où par pas pour qu que quel quelle qui s sa
+sans se sera seront ses si sien son sont sous sur t ta te tes toi ton tous tout
+toute toutes très tu un une vos votre vous y
+";
+
+const SPANISH: &str = "
+a al algo algunas algunos ante antes como con contra cual cuando de del desde
+donde durante e el él ella ellas ellos en entre era erais eran eras es esa esas
+ese eso esos esta está están estas este esto estos fue fueron ha había han hasta
+hay la las le les lo los más me mi mis mucho muy nada ni no nos nosotros o os
+otra otros para pero poco por porque que qué se sea ser si sí sin sobre son su
+sus también tan te tengo ti todo todos tu tus un una uno unos y ya yo
+";
+
+const GERMAN: &str = "
+aber alle allem allen aller alles als also am an ander andere anderen auch auf
+aus bei bin bis bist da damit dann das dass dein deine dem den denn der des dich
+die dies diese dieser dieses dir doch dort du durch ein eine einem einen einer
+eines er es etwas euch euer für hat hatte hier hin ich ihm ihn ihr ihre im in
+ist ja jede jedem jeden jeder jedes kann kein keine mein meine mich mir mit muss
+nach nicht nichts noch nun nur ob oder ohne sehr sein seine sich sie sind so
+über um und uns unser unter viel vom von vor war waren was weil wenn wer wie
+wieder will wir wird zu zum zur
+";
+
+fn builtin_list(language: Language) -> &'static str {
+	match language {
+		Language::English => ENGLISH,
+		Language::French => FRENCH,
+		Language::Spanish => SPANISH,
+		Language::German => GERMAN,
+	}
+}
+
+/// Words left out of the frequency table and of the most used words.
+/// Matching ignores case and the punctuation around words.
+#[derive(Clone, Default)]
+pub struct StopWords {
+	words: HashSet<String>,
+}
+
+fn clean(word: &str) -> String {
+	word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
+
+impl StopWords {
+	pub fn new() -> StopWords {
+		StopWords::default()
+	}
+
+	/// The stop words bundled for `language`.
+	pub fn builtin(language: Language) -> StopWords {
+		let mut stop_words = StopWords::new();
+		stop_words.add_builtin(language);
+		stop_words
+	}
+
+	/// Loads a list with one or more words per line; everything after a '#'
+	/// is a comment.
+	pub fn from_file(path: &Path) -> io::Result<StopWords> {
+		let mut stop_words = StopWords::new();
+		stop_words.add_file(path)?;
+		Ok(stop_words)
+	}
+
+	pub fn add_builtin(&mut self, language: Language) {
+		self.add_list(builtin_list(language));
+	}
+
+	pub fn add_file(&mut self, path: &Path) -> io::Result<()> {
+		let content = read_to_string(path)?;
+		for line in content.lines() {
+			let line = line.split('#').next().unwrap_or("");
+			self.add_list(line);
+		}
+		Ok(())
+	}
+
+	fn add_list(&mut self, list: &str) {
+		for word in list.split_whitespace() {
+			let word = clean(word);
+			if !word.is_empty() {
+				self.words.insert(word);
+			}
+		}
+	}
+
+	pub fn contains(&self, word: &str) -> bool {
+		!self.words.is_empty() && self.words.contains(&clean(word))
+	}
+
+	pub fn len(&self) -> usize {
+		self.words.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.words.is_empty()
+	}
+}
@@ -0,0 +1,396 @@
+
+use std::{fs::read_to_string, io, path::Path};
+use crate::{
+	diagnostic::Diagnostic,
+	extract::{Columns, LineExtractor, Markup},
+	language::Language,
+	segmentation,
+	sentences::SentenceSegmenter,
+};
+
+/// Names of the style rules, as given in the rules file and the diagnostics.
+pub const RULES: [&str; 5] = ["passive-voice", "weasel-words", "adverbs", "doubled-words", "long-sentence"];
+
+/// Words which hedge or inflate a statement without saying much.
+const WEASEL_WORDS: &[&str] = &[
+	"many", "various", "very", "fairly", "several", "extremely", "exceedingly", "quite", "remarkably", "few",
+	"surprisingly", "mostly", "largely", "huge", "tiny", "excellent", "interestingly", "significantly",
+	"substantially", "clearly", "vast", "relatively", "completely", "really", "somewhat", "arguably",
+	"basically", "virtually", "literally", "obviously",
+];
+
+/// Words ending with "ly" which are not adverbs.
+const NOT_ADVERBS: &[&str] = &[
+	"only", "family", "early", "reply", "apply", "supply", "fly", "july", "italy", "holy", "ugly", "lily",
+	"rely", "ally", "belly", "bully", "jelly", "folly", "rally", "tally", "silly", "hilly", "jolly", "wily",
+	"friendly", "lonely", "lovely", "lively", "likely", "unlikely", "daily", "weekly", "monthly", "yearly",
+	"costly", "curly", "elderly", "ghostly", "orderly", "assembly", "anomaly", "monopoly", "multiply",
+	"comply", "imply", "butterfly", "melancholy", "homely", "kindly", "manly", "sly", "smelly",
+	"woolly", "wobbly", "bubbly", "chilly", "oily", "scaly", "surly",
+];
+
+/// Forms of "to be" which start a passive construction.
+const BE: &[&str] = &["am", "is", "are", "was", "were", "be", "been", "being", "isn't", "aren't", "wasn't", "weren't"];
+
+/// Irregular past participles; the regular ones end with "ed".
+const PARTICIPLES: &[&str] = &[
+	"awoken", "beaten", "become", "begun", "bent", "bitten", "blown", "broken", "brought", "built", "bought",
+	"caught", "chosen", "come", "cut", "dealt", "done", "drawn", "driven", "drunk", "eaten", "fallen", "fed",
+	"felt", "forbidden", "forgiven", "forgotten", "fought", "found", "frozen", "given", "gone", "gotten",
+	"grown", "heard", "held", "hidden", "hit", "hung", "hurt", "kept", "known", "laid", "led", "left",
+	"lent", "let", "lost", "made", "meant", "met", "paid", "put", "read", "ridden", "risen", "run", "said",
+	"seen", "sent", "set", "shaken", "shown", "shut", "sold", "spent", "split", "spoken", "spread", "stolen",
+	"struck", "stuck", "sung", "sunk", "sworn", "taken", "taught", "thought", "thrown", "told", "torn",
+	"understood", "woken", "won", "worn", "written",
+];
+
+/// Words ending with "ed" which are not past participles.
+const NOT_PARTICIPLES: &[&str] = &[
+	"bed", "red", "need", "seed", "feed", "speed", "indeed", "hundred", "shed", "sled", "wed", "bred", "fled",
+	"naked", "sacred", "wicked", "rugged", "ragged", "crooked", "embed", "proceed", "exceed", "succeed",
+];
+
+fn is_adverb(word: &str) -> bool {
+	word.len() > 4 && word.ends_with("ly") && !NOT_ADVERBS.contains(&word)
+}
+
+fn is_participle(word: &str) -> bool {
+	PARTICIPLES.contains(&word) || (word.len() > 3 && word.ends_with("ed") && !NOT_PARTICIPLES.contains(&word))
+}
+
+/// Which style rules are checked, and their limits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StyleRules {
+	/// A form of "to be" followed by a past participle, as in "was written".
+	pub passive_voice: bool,
+	pub weasel_words: bool,
+	/// Sentences with more adverbs than `max_adverbs`.
+	pub adverbs: bool,
+	/// The same word twice in a row, as in "the the".
+	pub doubled_words: bool,
+	/// Sentences with more words than `max_sentence_words`.
+	pub long_sentence: bool,
+	pub max_adverbs: usize,
+	pub max_sentence_words: usize,
+}
+
+impl Default for StyleRules {
+	fn default() -> StyleRules {
+		StyleRules {
+			passive_voice: true,
+			weasel_words: true,
+			adverbs: true,
+			doubled_words: true,
+			long_sentence: true,
+			max_adverbs: 2,
+			max_sentence_words: 35,
+		}
+	}
+}
+
+fn invalid(line: usize, message: String) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, format!("{} at line {}", message, line))
+}
+
+impl StyleRules {
+	/// Turns the rule named `rule` on or off with "true" or "false". A
+	/// number turns "adverbs" and "long-sentence" on with that limit.
+	pub fn set(&mut self, rule: &str, value: &str) -> Result<(), String> {
+		if !RULES.contains(&rule) {
+			return Err(format!("unknown style rule '{}' (expected {})", rule, RULES.join(", ")));
+		}
+		let limit = value.parse::<usize>().ok();
+		let enabled = match value {
+			"true" | "on" => true,
+			"false" | "off" => false,
+			_ if limit.is_some() && (rule == "adverbs" || rule == "long-sentence") => true,
+			_ => return Err(format!("invalid value '{}' for the style rule '{}'", value, rule)),
+		};
+		match rule {
+			"passive-voice" => self.passive_voice = enabled,
+			"weasel-words" => self.weasel_words = enabled,
+			"adverbs" => {
+				self.adverbs = enabled;
+				self.max_adverbs = limit.unwrap_or(self.max_adverbs);
+			}
+			"doubled-words" => self.doubled_words = enabled,
+			"long-sentence" => {
+				self.long_sentence = enabled;
+				self.max_sentence_words = limit.unwrap_or(self.max_sentence_words);
+			}
+			_ => (),
+		}
+		Ok(())
+	}
+
+	/// Reads the rules from a file of `rule = value` lines, as given to
+	/// [`StyleRules::set`]; '#' starts a comment.
+	pub fn add_file(&mut self, path: &Path) -> io::Result<()> {
+		let content = read_to_string(path)?;
+		for (i, line) in content.lines().enumerate() {
+			let line = line.split('#').next().unwrap_or("").trim();
+			if line.is_empty() {
+				continue;
+			}
+			let (rule, value) = line.split_once('=').ok_or_else(|| invalid(i + 1, "expected 'rule = value'".to_string()))?;
+			let value = value.trim().trim_matches('"');
+			self.set(rule.trim().trim_matches('"'), value).map_err(|message| invalid(i + 1, message))?;
+		}
+		Ok(())
+	}
+}
+
+/// What the style check found in a text.
+#[derive(Clone, Default)]
+pub struct Style {
+	problems: usize,
+	diagnostics: Vec<Diagnostic>,
+}
+
+impl Style {
+	/// Adds the count of `other`. Positions belong to a single text: the
+	/// diagnostics are cleared.
+	pub fn merge(&mut self, other: &Style) {
+		self.problems += other.problems;
+		self.diagnostics.clear();
+	}
+
+	/// Number of problems found.
+	pub fn problems(&self) -> usize {
+		self.problems
+	}
+	/// The problems found, by line and column.
+	pub fn diagnostics(&self) -> &[Diagnostic] {
+		&self.diagnostics
+	}
+}
+
+/// A word of the text and where it is.
+struct Word {
+	text: String,
+	line: usize,
+	column: usize,
+}
+
+/// Applies the rules to the words of the lines, one sentence at a time.
+struct Checker {
+	rules: StyleRules,
+	/// The rules made of English words are only checked in English.
+	english: bool,
+	sentences: SentenceSegmenter,
+	blank_line: bool,
+	/// Line and column of the first word of the sentence.
+	start: Option<(usize, usize)>,
+	/// Number of words of the sentence so far.
+	words: usize,
+	adverbs: Vec<String>,
+	/// The previous word, unless punctuation follows it.
+	previous: Option<String>,
+	/// A form of "to be", and the number of words skipped after it, as
+	/// "not" and "easily" in "is not easily understood".
+	be: Option<(Word, usize)>,
+	diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker {
+	fn check_line(&mut self, number: usize, line: &str, extracted: &str) {
+		let mut columns = Columns::new(line);
+		for piece in extracted.split_inclusive('\n') {
+			for token in piece.split_whitespace() {
+				self.blank_line = false;
+				let column = columns.find(token);
+				let words: Vec<&str> = segmentation::words(token).collect();
+				if self.sentences.push(token, words.len()) {
+					self.end_sentence();
+				}
+				let mut from = 0;
+				for (i, word) in words.iter().enumerate() {
+					let at = from + token[from..].find(word).unwrap_or(0);
+					from = at + word.len();
+					let word = Word { text: word.to_lowercase(), line: number, column: column + segmentation::grapheme_count(&token[..at]) };
+					self.add_word(word, i + 1 == words.len() && token.len() == from);
+				}
+			}
+			if piece.ends_with('\n') {
+				if self.blank_line {
+					self.sentences.paragraph_break();
+					self.end_sentence();
+				}
+				self.blank_line = true;
+			}
+		}
+	}
+
+	/// Checks the next `word`; `last` tells whether it ends its token,
+	/// without punctuation after it.
+	fn add_word(&mut self, word: Word, last: bool) {
+		if self.start.is_none() {
+			self.start = Some((word.line, word.column));
+		}
+		self.words += 1;
+
+		if self.rules.doubled_words && self.previous.as_ref() == Some(&word.text) {
+			let message = format!("'{}' is repeated", word.text);
+			self.diagnostics.push(Diagnostic::new(word.line, word.column, "doubled-words", message));
+		}
+		if self.english {
+			if self.rules.weasel_words && WEASEL_WORDS.contains(&word.text.as_str()) {
+				let message = format!("'{}' is a weasel word", word.text);
+				self.diagnostics.push(Diagnostic::new(word.line, word.column, "weasel-words", message));
+			}
+			if is_adverb(&word.text) {
+				self.adverbs.push(word.text.clone());
+			}
+			if self.rules.passive_voice {
+				self.be = match self.be.take() {
+					Some((be, _)) if is_participle(&word.text) => {
+						let message = format!("'{} {}' may be passive voice", be.text, word.text);
+						self.diagnostics.push(Diagnostic::new(be.line, be.column, "passive-voice", message));
+						None
+					}
+					Some((be, skipped)) if skipped < 2 && (word.text == "not" || is_adverb(&word.text)) => {
+						Some((be, skipped + 1))
+					}
+					_ => None,
+				};
+				if self.be.is_none() && BE.contains(&word.text.as_str()) {
+					self.be = Some((Word { text: word.text.clone(), ..word }, 0));
+				}
+			}
+		}
+		self.previous = if last { Some(word.text) } else { None };
+	}
+
+	fn end_sentence(&mut self) {
+		if let Some((line, column)) = self.start.take() {
+			if self.rules.long_sentence && self.words > self.rules.max_sentence_words {
+				let message = format!("sentence is {} words long, above {}", self.words, self.rules.max_sentence_words);
+				self.diagnostics.push(Diagnostic::new(line, column, "long-sentence", message));
+			}
+			if self.rules.adverbs && self.adverbs.len() > self.rules.max_adverbs {
+				let message = format!(
+					"sentence has {} adverbs ({}), above {}",
+					self.adverbs.len(),
+					self.adverbs.join(", "),
+					self.rules.max_adverbs
+				);
+				self.diagnostics.push(Diagnostic::new(line, column, "adverbs", message));
+			}
+		}
+		self.words = 0;
+		self.adverbs.clear();
+		self.previous = None;
+		self.be = None;
+	}
+}
+
+/// Running style check of a text, line by line. The markup of every line is
+/// left out; the sentences are the ones of [`SentenceSegmenter`].
+pub struct StyleChecker {
+	lines: LineExtractor,
+	checker: Checker,
+}
+
+impl StyleChecker {
+	pub fn new(rules: StyleRules, language: Language, markup: Markup) -> StyleChecker {
+		let checker = Checker {
+			rules,
+			english: language == Language::English,
+			sentences: SentenceSegmenter::new(language),
+			blank_line: true,
+			start: None,
+			words: 0,
+			adverbs: Vec::new(),
+			previous: None,
+			be: None,
+			diagnostics: Vec::new(),
+		};
+		StyleChecker { lines: LineExtractor::new(markup), checker }
+	}
+
+	/// Feeds the next piece of the text; a line can be cut between pieces.
+	pub fn push(&mut self, text: &str) {
+		let checker = &mut self.checker;
+		self.lines.push(text, |number, line, extracted| checker.check_line(number, line, extracted));
+	}
+
+	pub fn finish(mut self) -> Style {
+		let checker = &mut self.checker;
+		self.lines.finish(|number, line, extracted| checker.check_line(number, line, extracted));
+		self.checker.end_sentence();
+		let mut diagnostics = self.checker.diagnostics;
+		diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+		Style { problems: diagnostics.len(), diagnostics }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// The rule, line and column of every problem of `text`.
+	fn check(text: &str, rules: StyleRules, language: Language, markup: Markup) -> Vec<(&'static str, usize, usize)> {
+		let mut checker = StyleChecker::new(rules, language, markup);
+		// Cut in the middle of words and lines, as the text is read.
+		for piece in text.as_bytes().chunks(3) {
+			checker.push(std::str::from_utf8(piece).unwrap());
+		}
+		checker.finish().diagnostics().iter().map(|diagnostic| (diagnostic.rule, diagnostic.line, diagnostic.column)).collect()
+	}
+
+	fn english(text: &str) -> Vec<(&'static str, usize, usize)> {
+		check(text, StyleRules::default(), Language::English, Markup::Plain)
+	}
+
+	#[test]
+	fn passive_voice() {
+		assert_eq!(english("The book was written by her."), vec![("passive-voice", 1, 10)]);
+		assert_eq!(english("It is not easily understood."), vec![("passive-voice", 1, 4)]);
+		assert_eq!(english("He was at the bed."), vec![]);
+		assert_eq!(english("She was tired and\nwas taken home."), vec![("passive-voice", 1, 5), ("passive-voice", 2, 1)]);
+	}
+
+	#[test]
+	fn weasel_words_and_adverbs() {
+		assert_eq!(english("This is very good."), vec![("weasel-words", 1, 9)]);
+		assert_eq!(english("He ran quickly, quietly and\n  silently home."), vec![("adverbs", 1, 1)]);
+		assert_eq!(english("He ran quickly and quietly. The family left early."), vec![]);
+	}
+
+	#[test]
+	fn doubled_words() {
+		assert_eq!(english("Paris in the\nthe spring."), vec![("doubled-words", 2, 1)]);
+		assert_eq!(english("It is, is it?"), vec![]);
+	}
+
+	#[test]
+	fn long_sentences() {
+		let mut rules = StyleRules::default();
+		rules.set("long-sentence", "4").unwrap();
+		let text = "Short one. This sentence has six words.\n\nA title\n\nA line break\ndoes not end it.";
+		assert_eq!(check(text, rules, Language::English, Markup::Plain), vec![("long-sentence", 1, 12), ("long-sentence", 5, 1)]);
+	}
+
+	#[test]
+	fn rules_can_be_turned_off() {
+		let mut rules = StyleRules::default();
+		rules.set("weasel-words", "off").unwrap();
+		rules.set("passive-voice", "false").unwrap();
+		assert_eq!(check("It was very clearly written.", rules, Language::English, Markup::Plain), vec![]);
+		assert!(StyleRules::default().set("colour", "on").is_err());
+		assert!(StyleRules::default().set("doubled-words", "3").is_err());
+	}
+
+	#[test]
+	fn english_word_rules_only_apply_to_english() {
+		let text = "Il est very content, clearly. Il est est là.";
+		assert_eq!(check(text, StyleRules::default(), Language::French, Markup::Plain), vec![("doubled-words", 1, 38)]);
+		assert_eq!(english(text), vec![("weasel-words", 1, 8), ("weasel-words", 1, 22), ("doubled-words", 1, 38)]);
+	}
+
+	#[test]
+	fn positions_are_in_the_text_as_read() {
+		let text = "# Title\n\nSome *very* nice text.\n\n```\nvery very\n```\n";
+		assert_eq!(check(text, StyleRules::default(), Language::English, Markup::Markdown), vec![("weasel-words", 3, 7)]);
+	}
+}
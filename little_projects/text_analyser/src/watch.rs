@@ -0,0 +1,115 @@
+
+use std::{fs, process, thread, time::{Duration, SystemTime}};
+use text_analyser::{compare, error::Error, report::Format, Stats};
+use crate::{analyse_input, cli::{Args, Input}, print_output};
+
+/// What tells that a file was saved: its modification time and its size,
+/// as a save can keep the same time on coarse file systems. It is polled
+/// rather than watched with inotify, which is only on Linux and misses the
+/// changes made over network file systems.
+fn version(input: &Input) -> Option<(SystemTime, u64)> {
+	match input {
+		Input::File(path) => fs::metadata(path).ok().and_then(|meta| Some((meta.modified().ok()?, meta.len()))),
+		Input::Stdin => None,
+	}
+}
+
+/// The figures that changed from `before` to `after`, as "+120 words,
+/// -2 phrases".
+fn changes(before: &Stats, after: &Stats) -> String {
+	compare::metrics(before)
+		.into_iter()
+		.zip(compare::metrics(after))
+		.filter_map(|((name, old), (_, new))| {
+			let delta = new - old;
+			if delta == 0.0 || delta.is_nan() {
+				None
+			} else if old.fract() == 0.0 && new.fract() == 0.0 {
+				Some(format!("{:+} {}", delta as i64, name))
+			} else {
+				Some(format!("{:+.2} {}", delta, name))
+			}
+		})
+		.collect::<Vec<String>>()
+		.join(", ")
+}
+
+struct Watched {
+	input: Input,
+	version: Option<(SystemTime, u64)>,
+	stats: Option<Stats>,
+	change: String,
+	error: Option<Error>,
+}
+
+fn draw(files: &[Watched], errors: &[Error], args: &Args) {
+	let mut analysed = Vec::new();
+	let mut changes = Vec::new();
+	for file in files {
+		if let Some(stats) = &file.stats {
+			analysed.push((file.input.name(), stats.clone()));
+			changes.push(file.change.clone());
+		}
+	}
+	if args.format == Format::Text {
+		// Clears the terminal and moves the cursor to its top.
+		print!("\x1b[2J\x1b[H");
+	}
+	print_output(&analysed, args, &changes);
+	for error in errors.iter().chain(files.iter().filter_map(|file| file.error.as_ref())) {
+		eprintln!("{}", error);
+	}
+	if args.format == Format::Text {
+		println!();
+		println!("Watching {} files, press Ctrl-C to stop", files.len());
+	}
+}
+
+/// Shows the reports of `inputs`, then checks the files every
+/// `args.interval` milliseconds and analyses again the ones saved since,
+/// until the process is stopped. `errors` are the paths that could not be
+/// watched.
+pub fn run(inputs: Vec<Input>, results: Vec<Result<Stats, Error>>, errors: Vec<Error>, args: &Args) -> ! {
+	if inputs.iter().any(|input| matches!(input, Input::Stdin)) {
+		let error = Error::Usage("The standard input cannot be watched".to_string());
+		eprintln!("{}", error);
+		process::exit(error.exit_code());
+	}
+	let mut files: Vec<Watched> = inputs
+		.into_iter()
+		.zip(results)
+		.map(|(input, result)| {
+			let version = version(&input);
+			let (stats, error) = match result {
+				Ok(stats) => (Some(stats), None),
+				Err(error) => (None, Some(error)),
+			};
+			Watched { input, version, stats, change: String::new(), error }
+		})
+		.collect();
+	draw(&files, &errors, args);
+
+	loop {
+		thread::sleep(Duration::from_millis(args.interval));
+		let mut changed = false;
+		for file in &mut files {
+			let version = version(&file.input);
+			if version == file.version {
+				continue;
+			}
+			file.version = version;
+			changed = true;
+			match analyse_input(&file.input, args) {
+				Ok(stats) => {
+					file.change = file.stats.as_ref().map(|before| changes(before, &stats)).unwrap_or_default();
+					file.stats = Some(stats);
+					file.error = None;
+				}
+				Err(error) => file.error = Some(error),
+			}
+		}
+		if changed {
+			draw(&files, &errors, args);
+		}
+	}
+}
@@ -0,0 +1,105 @@
+
+use std::io::BufReader;
+use text_analyser::{encoding::Encoding, Stats, StatsBuilder};
+
+/// The figures of `stats` which do not depend on how the text was given.
+fn summary(stats: &Stats) -> (usize, usize, usize, usize, usize, usize, usize, Vec<String>, usize, String) {
+	(
+		stats.phrases(),
+		stats.words(),
+		stats.chars(),
+		stats.code_points(),
+		stats.bytes(),
+		stats.lines(),
+		stats.syllables(),
+		stats.most_used_words().to_vec(),
+		stats.most_used_count(),
+		format!("{:?} {:?}", stats.word_lengths().counts(), stats.phrase_lengths().counts()),
+	)
+}
+
+/// Checks that reading `bytes` a few bytes at a time gives the figures of
+/// `text` given at once.
+fn assert_same(text: &str, bytes: &[u8]) {
+	let expected = summary(&Stats::analyse(text));
+	for capacity in 1..=8 {
+		let stats = Stats::from_reader(BufReader::with_capacity(capacity, bytes)).unwrap();
+		assert_eq!(summary(&stats), expected, "buffers of {} bytes", capacity);
+	}
+}
+
+#[test]
+fn utf8_cut_between_buffers() {
+	let text = "Où est l'été ? Ça dépend… Ærøskøbing, Zürich et Kraków sont loin.\nÜber 3.50 € pro Tag!\n";
+	assert_same(text, text.as_bytes());
+}
+
+#[test]
+fn grapheme_clusters_cut_between_buffers() {
+	let text = "Cafe\u{301} na\u{308}ive 👍🏽 family 👨‍👩‍👧 flags 🇫🇷🇩🇪. Second sentence here.";
+	assert_same(text, text.as_bytes());
+}
+
+#[test]
+fn crlf_cut_between_buffers() {
+	let text = "First line.\r\nSecond line.\r\n\r\nA new paragraph\r\nwithout an end";
+	assert_same(text, text.as_bytes());
+}
+
+#[test]
+fn byte_order_mark_cut_between_buffers() {
+	let text = "The byte order mark is read before the rest. Naïve café.\n";
+	let mut utf8 = vec![0xEF, 0xBB, 0xBF];
+	utf8.extend_from_slice(text.as_bytes());
+	assert_same(text, &utf8);
+
+	let mut utf16 = vec![0xFF, 0xFE];
+	utf16.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+	assert_same(text, &utf16);
+}
+
+/// Reads `bytes` with the encoding given, if any, a few bytes at a time.
+fn read(bytes: &[u8], encoding: Option<Encoding>) -> std::io::Result<(Stats, Encoding)> {
+	let mut builder = StatsBuilder::new();
+	let used = builder.read(BufReader::with_capacity(3, bytes), encoding)?;
+	Ok((builder.finish(), used))
+}
+
+#[test]
+fn given_encoding_overrides_the_detection() {
+	// Valid UTF-8 read as latin-1 gives two characters for each accent.
+	let text = "Un été.";
+	let (stats, used) = read(text.as_bytes(), Some(Encoding::Latin1)).unwrap();
+	assert_eq!((used, stats.encoding(), stats.chars()), (Encoding::Latin1, Some(Encoding::Latin1), 8));
+	let (stats, used) = read(text.as_bytes(), None).unwrap();
+	assert_eq!((used, stats.code_points()), (Encoding::Utf8, 6));
+
+	// UTF-16 without a byte order mark read as the other byte order.
+	let utf16: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+	let (stats, used) = read(&utf16, None).unwrap();
+	assert_eq!((used, stats.frequencies().count("été")), (Encoding::Utf16Le, 1));
+	let (stats, used) = read(&utf16, Some(Encoding::Utf16Be)).unwrap();
+	assert_eq!(used, Encoding::Utf16Be);
+	assert_eq!(stats.frequencies().count("été"), 0);
+}
+
+#[test]
+fn given_encoding_keeps_a_mismatched_byte_order_mark() {
+	let mut bytes = vec![0xEF, 0xBB, 0xBF];
+	bytes.extend_from_slice("été".as_bytes());
+	// The byte order mark of the encoding given is skipped.
+	let (stats, _) = read(&bytes, Some(Encoding::Utf8)).unwrap();
+	assert_eq!(stats.chars(), 3);
+	// Another encoding reads it as text.
+	let (stats, _) = read(&bytes, Some(Encoding::Windows1252)).unwrap();
+	assert_eq!(stats.chars(), 8);
+}
+
+#[test]
+fn given_utf8_does_not_fall_back() {
+	let bytes = b"Caf\xE9 au lait.";
+	let error = read(bytes, Some(Encoding::Utf8)).err().map(|error| error.kind());
+	assert_eq!(error, Some(std::io::ErrorKind::InvalidData));
+	let (stats, used) = read(bytes, None).unwrap();
+	assert_eq!((used, stats.words()), (Encoding::Windows1252, 3));
+}